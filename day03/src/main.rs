@@ -4,91 +4,415 @@ use std::fs::File;
 use std::hash::Hash;
 use std::io::prelude::*;
 use std::io::BufReader;
+use std::str::FromStr;
+use std::time::Instant;
 
 fn main() -> Result<()> {
     let input = read_input("input.txt")?;
-    let gamma_rate_str = most_common_chars(&input)?;
-    let gamma_rate = i32::from_str_radix(&gamma_rate_str, 2)?;
-    let epsilon_rate_str = least_common_chars(&input)?;
-    let epsilon_rate = i32::from_str_radix(&epsilon_rate_str, 2)?;
-    println!("power consumption: {}", gamma_rate * epsilon_rate);
-
-    let oxigen_generator_rating_str = most_common_string(&input)?;
-    let oxigen_generator_rating = i32::from_str_radix(&oxigen_generator_rating_str, 2)?;
-    let co2_scrubber_rating_str = least_common_string(&input)?;
-    let co2_scrubber_rating = i32::from_str_radix(&co2_scrubber_rating_str, 2)?;
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == "--bench") {
+        return bench(&input, 1000);
+    }
+    if let Some(i) = args.iter().position(|a| a == "--analyze") {
+        let path = args.get(i + 1).map(|p| p.as_str()).unwrap_or("input.txt");
+        return analyze(&read_input(path)?);
+    }
+    let tie = match args.iter().position(|a| a == "--tie") {
+        Some(i) => Some(
+            args.get(i + 1)
+                .ok_or(anyhow!("missing tie policy"))?
+                .parse::<TiePolicy>()?,
+        ),
+        None => None,
+    };
+
+    let gamma_rates = parse_binary(most_common_chars(&input, tie.unwrap_or(TiePolicy::Error))?)?;
+    let epsilon_rates = parse_binary(least_common_chars(&input, tie.unwrap_or(TiePolicy::Error))?)?;
+    println!(
+        "power consumption: {}",
+        products(&gamma_rates, &epsilon_rates)
+    );
+
+    let oxigen_generator_ratings = parse_binary(most_common_string(
+        &input,
+        tie.unwrap_or(TiePolicy::PreferOne),
+    )?)?;
+    let co2_scrubber_ratings = parse_binary(least_common_string(
+        &input,
+        tie.unwrap_or(TiePolicy::PreferZero),
+    )?)?;
     println!(
         "life support rating: {}",
-        oxigen_generator_rating * co2_scrubber_rating
+        products(&oxigen_generator_ratings, &co2_scrubber_ratings)
     );
 
     Ok(())
 }
 
+fn analyze(records: &[String]) -> Result<()> {
+    let frequencies = ColumnFrequencies::analyze(records.iter().map(|r| r.chars()))?;
+    let consensus = frequencies.consensus();
+    let entropy = frequencies.entropy();
+    for (i, (c, e)) in consensus.iter().zip(entropy.iter()).enumerate() {
+        let histogram = frequencies
+            .histogram(i)
+            .iter()
+            .map(|(c, n)| format!("{}:{}", c, n))
+            .collect::<Vec<String>>()
+            .join(" ");
+        println!("{:>4} {} {:.4} {}", i, c.unwrap_or('?'), e, histogram);
+    }
+    println!(
+        "consensus: {}",
+        consensus
+            .iter()
+            .map(|c| c.unwrap_or('?'))
+            .collect::<String>()
+    );
+    Ok(())
+}
+
+fn parse_binary(strings: Vec<String>) -> Result<Vec<i64>> {
+    strings
+        .iter()
+        .map(|s| Ok(i64::from_str_radix(s, 2)?))
+        .collect()
+}
+
+fn products(xs: &[i64], ys: &[i64]) -> String {
+    xs.iter()
+        .flat_map(|x| ys.iter().map(move |y| (x * y).to_string()))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn bench(input: &[String], iterations: u32) -> Result<()> {
+    let start = Instant::now();
+    let mut string_result = (0, 0);
+    for _ in 0..iterations {
+        let gamma_rate = single(most_common_chars(input, TiePolicy::Error)?)?;
+        let epsilon_rate = single(least_common_chars(input, TiePolicy::Error)?)?;
+        let oxigen = single(most_common_string(input, TiePolicy::PreferOne)?)?;
+        let co2 = single(least_common_string(input, TiePolicy::PreferZero)?)?;
+        string_result = (
+            i32::from_str_radix(&gamma_rate, 2)? * i32::from_str_radix(&epsilon_rate, 2)?,
+            i32::from_str_radix(&oxigen, 2)? * i32::from_str_radix(&co2, 2)?,
+        );
+    }
+    let string_elapsed = start.elapsed();
+
+    let start = Instant::now();
+    let mut report_result = (0, 0);
+    for _ in 0..iterations {
+        let report = Report::parse(input)?;
+        report_result = (
+            single(report.gamma_rate(TiePolicy::Error)?)?
+                * single(report.epsilon_rate(TiePolicy::Error)?)?,
+            single(report.oxigen_generator_rating(TiePolicy::PreferOne)?)?
+                * single(report.co2_scrubber_rating(TiePolicy::PreferZero)?)?,
+        );
+    }
+    let report_elapsed = start.elapsed();
+
+    if string_result != (report_result.0 as i32, report_result.1 as i32) {
+        return Err(anyhow!(
+            "results differ: {:?} vs {:?}",
+            string_result,
+            report_result
+        ));
+    }
+    println!("results: {:?}", string_result);
+    println!(
+        "strings: {:?}/iter, bits: {:?}/iter",
+        string_elapsed / iterations,
+        report_elapsed / iterations
+    );
+    Ok(())
+}
+
+fn single<T: std::fmt::Debug>(results: Vec<T>) -> Result<T> {
+    if results.len() != 1 {
+        return Err(anyhow!("expected a single result, got {:?}", results));
+    }
+    results
+        .into_iter()
+        .next()
+        .ok_or(anyhow!("expected a single result"))
+}
+
 fn read_input<P: AsRef<std::path::Path>>(path: P) -> Result<Vec<String>> {
     let file = File::open(path)?;
     BufReader::new(file).lines().map(|l| Ok(l?)).collect()
 }
 
-fn most_common_chars(strings: &Vec<String>) -> Result<String> {
-    let str_len = strings.first().ok_or(anyhow!("empty input!"))?.len();
-    let chars = (0..str_len)
-        .map(|i| {
-            let chars = chars_at(i, strings)?;
-            most_common_char(chars)
-        })
-        .collect::<Result<Vec<char>>>()?;
-    Ok(chars.into_iter().collect())
+#[derive(Debug)]
+struct Report {
+    width: usize,
+    readings: Vec<u64>,
+    columns: Vec<Vec<u64>>,
 }
 
-fn least_common_chars(strings: &Vec<String>) -> Result<String> {
-    let str_len = strings.first().ok_or(anyhow!("empty input!"))?.len();
-    let chars = (0..str_len)
-        .map(|i| {
-            let chars = chars_at(i, strings)?;
-            least_common_char(chars)
+impl Report {
+    fn parse<S: AsRef<str>>(strings: &[S]) -> Result<Report> {
+        let width = strings
+            .first()
+            .ok_or(anyhow!("empty input!"))?
+            .as_ref()
+            .len();
+        if width == 0 || width > 64 {
+            return Err(anyhow!("unsupported reading width: {}", width));
+        }
+
+        let readings = strings
+            .iter()
+            .map(|s| {
+                let s = s.as_ref();
+                if s.len() != width {
+                    return Err(anyhow!("wrong reading length: {:?}", s));
+                }
+                Ok(u64::from_str_radix(s, 2)?)
+            })
+            .collect::<Result<Vec<u64>>>()?;
+
+        let mut columns = vec![vec![0u64; readings.len().div_ceil(64)]; width];
+        for (i, r) in readings.iter().enumerate() {
+            for (c, column) in columns.iter_mut().enumerate() {
+                if r & bit_mask(width, c) != 0 {
+                    column[i / 64] |= 1 << (i % 64);
+                }
+            }
+        }
+
+        Ok(Report {
+            width,
+            readings,
+            columns,
         })
-        .collect::<Result<Vec<char>>>()?;
-    Ok(chars.into_iter().collect())
+    }
+
+    fn ones_at(&self, column: usize) -> usize {
+        self.columns[column]
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum()
+    }
+
+    fn gamma_rate(&self, tie: TiePolicy) -> Result<Vec<u64>> {
+        self.rate(|ones, zeros| ones > zeros, tie)
+    }
+
+    fn epsilon_rate(&self, tie: TiePolicy) -> Result<Vec<u64>> {
+        self.rate(|ones, zeros| zeros == 0 || (ones != 0 && ones < zeros), tie)
+    }
+
+    fn rate<F: Fn(usize, usize) -> bool>(&self, select_one: F, tie: TiePolicy) -> Result<Vec<u64>> {
+        let mut rates = vec![0];
+        for c in 0..self.width {
+            let mask = bit_mask(self.width, c);
+            let ones = self.ones_at(c);
+            let zeros = self.readings.len() - ones;
+            let bits = if ones == zeros {
+                resolve_bits(tie)?
+            } else {
+                vec![select_one(ones, zeros)]
+            };
+            rates = rates
+                .iter()
+                .flat_map(|r| bits.iter().map(move |&b| if b { r | mask } else { *r }))
+                .collect();
+        }
+        Ok(rates)
+    }
+
+    fn oxigen_generator_rating(&self, tie: TiePolicy) -> Result<Vec<u64>> {
+        self.rating(|ones, zeros| ones > zeros, tie)
+    }
+
+    fn co2_scrubber_rating(&self, tie: TiePolicy) -> Result<Vec<u64>> {
+        self.rating(|ones, zeros| zeros == 0 || (ones != 0 && ones < zeros), tie)
+    }
+
+    fn rating<F: Fn(usize, usize) -> bool>(
+        &self,
+        keep_ones: F,
+        tie: TiePolicy,
+    ) -> Result<Vec<u64>> {
+        let mut readings = self.readings.clone();
+        let mut ratings = vec![];
+        let mut ranges = vec![(0, readings.len(), 0)];
+        while let Some((start, end, c)) = ranges.pop() {
+            if end - start == 1 {
+                ratings.push(readings[start]);
+                continue;
+            }
+            if c == self.width {
+                return Err(anyhow!("failed to find a single rating"));
+            }
+            let mask = bit_mask(self.width, c);
+            let split = start + partition(&mut readings[start..end], |r| r & mask != 0);
+            let ones = split - start;
+            let zeros = end - split;
+            let bits = if ones == zeros {
+                resolve_bits(tie)?
+            } else {
+                vec![keep_ones(ones, zeros)]
+            };
+            for b in bits.into_iter().rev() {
+                if b {
+                    ranges.push((start, split, c + 1));
+                } else {
+                    ranges.push((split, end, c + 1));
+                }
+            }
+        }
+        if ratings.is_empty() {
+            return Err(anyhow!("failed to find a single rating"));
+        }
+        Ok(ratings)
+    }
+}
+
+fn resolve_bits(tie: TiePolicy) -> Result<Vec<bool>> {
+    Ok(tie
+        .resolve(vec!['0', '1'])?
+        .into_iter()
+        .map(|c| c == '1')
+        .collect())
+}
+
+fn bit_mask(width: usize, column: usize) -> u64 {
+    1 << (width - 1 - column)
+}
+
+fn partition<F: Fn(u64) -> bool>(values: &mut [u64], pred: F) -> usize {
+    let mut split = 0;
+    for i in 0..values.len() {
+        if pred(values[i]) {
+            values.swap(split, i);
+            split += 1;
+        }
+    }
+    split
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TiePolicy {
+    PreferOne,
+    PreferZero,
+    Error,
+    KeepBoth,
+}
+
+impl FromStr for TiePolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "prefer-one" => Ok(TiePolicy::PreferOne),
+            "prefer-zero" => Ok(TiePolicy::PreferZero),
+            "error" => Ok(TiePolicy::Error),
+            "keep-both" => Ok(TiePolicy::KeepBoth),
+            _ => Err(anyhow!("unknown tie policy: {:?}", s)),
+        }
+    }
+}
+
+impl TiePolicy {
+    fn resolve(&self, tied: Vec<char>) -> Result<Vec<char>> {
+        let preferred = match self {
+            TiePolicy::PreferOne => '1',
+            TiePolicy::PreferZero => '0',
+            TiePolicy::Error => return Err(anyhow!("more than one selected elements")),
+            TiePolicy::KeepBoth => return Ok(tied),
+        };
+        if tied.contains(&preferred) {
+            Ok(vec![preferred])
+        } else {
+            Err(anyhow!("tie does not involve {:?}: {:?}", preferred, tied))
+        }
+    }
 }
 
-fn most_common_string(strings: &Vec<String>) -> Result<String> {
-    select_string_by_char(strings, most_common_char, '1')
+fn most_common_chars(strings: &[String], tie: TiePolicy) -> Result<Vec<String>> {
+    let frequencies = ColumnFrequencies::analyze(strings.iter().map(|s| s.chars()))?;
+    select_chars(frequencies.most_common(), tie)
 }
 
-fn least_common_string(strings: &Vec<String>) -> Result<String> {
-    select_string_by_char(strings, least_common_char, '0')
+fn least_common_chars(strings: &[String], tie: TiePolicy) -> Result<Vec<String>> {
+    let frequencies = ColumnFrequencies::analyze(strings.iter().map(|s| s.chars()))?;
+    select_chars(frequencies.least_common(), tie)
 }
 
-fn select_string_by_char<F>(strings: &Vec<String>, select_char: F, default: char) -> Result<String>
+fn select_chars(candidates: Vec<Vec<char>>, tie: TiePolicy) -> Result<Vec<String>> {
+    let mut results = vec![String::new()];
+    for selected in candidates {
+        let chars = if selected.len() > 1 {
+            tie.resolve(selected)?
+        } else {
+            selected
+        };
+        results = results
+            .iter()
+            .flat_map(|r| {
+                chars.iter().map(move |c| {
+                    let mut r = r.clone();
+                    r.push(*c);
+                    r
+                })
+            })
+            .collect();
+    }
+    Ok(results)
+}
+
+fn most_common_string(strings: &[String], tie: TiePolicy) -> Result<Vec<String>> {
+    select_string_by_char(strings, most_common_char, tie)
+}
+
+fn least_common_string(strings: &[String], tie: TiePolicy) -> Result<Vec<String>> {
+    select_string_by_char(strings, least_common_char, tie)
+}
+
+fn select_string_by_char<F>(
+    strings: &[String],
+    select_char: F,
+    tie: TiePolicy,
+) -> Result<Vec<String>>
 where
-    F: Fn(Vec<char>) -> Result<char>,
+    F: Fn(Vec<char>, TiePolicy) -> Result<Vec<char>>,
 {
-    let mut result: Vec<String> = strings.to_owned();
-    let mut i = 0;
-    while result.len() > 1 {
-        let selected_char = select_char(chars_at(i, &result)?).unwrap_or(default);
-        result = result
-            .to_owned()
-            .into_iter()
-            .filter_map(|s| {
-                let c = s.chars().nth(i)?;
-                if c == selected_char {
-                    Some(s)
-                } else {
-                    None
-                }
-            })
+    let result = select_string_by_char_from(strings.to_owned(), 0, &select_char, tie)?;
+    if result.is_empty() {
+        return Err(anyhow!("failed to find most common string"));
+    }
+    Ok(result)
+}
+
+fn select_string_by_char_from<F>(
+    strings: Vec<String>,
+    i: usize,
+    select_char: &F,
+    tie: TiePolicy,
+) -> Result<Vec<String>>
+where
+    F: Fn(Vec<char>, TiePolicy) -> Result<Vec<char>>,
+{
+    if strings.len() <= 1 {
+        return Ok(strings);
+    }
+    let mut result = vec![];
+    for selected_char in select_char(chars_at(i, &strings)?, tie)? {
+        let branch = strings
+            .iter()
+            .filter(|s| s.chars().nth(i) == Some(selected_char))
+            .cloned()
             .collect();
-        i += 1;
+        result.extend(select_string_by_char_from(branch, i + 1, select_char, tie)?);
     }
-    result
-        .into_iter()
-        .nth(0)
-        .ok_or(anyhow!("failed to find most common string"))
+    Ok(result)
 }
 
-fn chars_at(index: usize, strings: &Vec<String>) -> Result<Vec<char>> {
+fn chars_at(index: usize, strings: &[String]) -> Result<Vec<char>> {
     strings
         .iter()
         .map(|s| {
@@ -99,33 +423,46 @@ fn chars_at(index: usize, strings: &Vec<String>) -> Result<Vec<char>> {
         .collect()
 }
 
-fn most_common_char<I: IntoIterator<Item = char>>(chars: I) -> Result<char> {
-    select_element_by_count(chars, |n1, n2| n1 > n2)
+fn most_common_char<I: IntoIterator<Item = char>>(chars: I, tie: TiePolicy) -> Result<Vec<char>> {
+    let selected = select_elements_by_count(chars, |n1, n2| n1 > n2)?;
+    if selected.len() > 1 {
+        return tie.resolve(selected);
+    }
+    Ok(selected)
 }
 
-fn least_common_char<I: IntoIterator<Item = char>>(chars: I) -> Result<char> {
-    select_element_by_count(chars, |n1, n2| n1 < n2)
+fn least_common_char<I: IntoIterator<Item = char>>(chars: I, tie: TiePolicy) -> Result<Vec<char>> {
+    let selected = select_elements_by_count(chars, |n1, n2| n1 < n2)?;
+    if selected.len() > 1 {
+        return tie.resolve(selected);
+    }
+    Ok(selected)
 }
 
-fn select_element_by_count<T, I, F>(i: I, cmp_count: F) -> Result<T>
+fn select_elements_by_count<T, I, F>(i: I, cmp_count: F) -> Result<Vec<T>>
 where
     I: IntoIterator<Item = T>,
-    T: Eq + Hash + Copy,
+    T: Eq + Hash + Copy + Ord,
+    F: Fn(&usize, &usize) -> bool,
+{
+    select_by_count(&count(i), cmp_count).ok_or(anyhow!("empty input"))
+}
+
+fn select_by_count<T, F>(counts: &HashMap<T, usize>, cmp_count: F) -> Option<Vec<T>>
+where
+    T: Eq + Hash + Copy + Ord,
     F: Fn(&usize, &usize) -> bool,
 {
-    let counts = count(i);
     let selected = counts
         .iter()
-        .reduce(|p1, p2| if cmp_count(p1.1, p2.1) { p1 } else { p2 })
-        .ok_or(anyhow!("empty input"))?;
-    let n_of_selected = counts
+        .reduce(|p1, p2| if cmp_count(p1.1, p2.1) { p1 } else { p2 })?;
+    let mut elements: Vec<T> = counts
         .iter()
         .filter(|&(_, count)| count == selected.1)
-        .count();
-    if n_of_selected > 1 {
-        return Err(anyhow!("more than one selected elements"));
-    }
-    Ok(*selected.0)
+        .map(|(e, _)| *e)
+        .collect();
+    elements.sort_unstable();
+    Some(elements)
 }
 
 fn count<T, I>(i: I) -> HashMap<T, usize>
@@ -135,14 +472,110 @@ where
 {
     let mut counts = HashMap::<T, usize>::new();
     for c in i {
-        counts.entry(c).and_modify(|e| *e += 1).or_insert(0);
+        *counts.entry(c).or_insert(0) += 1;
     }
     counts
 }
 
+#[derive(Debug)]
+struct ColumnFrequencies<T> {
+    records: usize,
+    columns: Vec<HashMap<T, usize>>,
+}
+
+impl<T: Eq + Hash + Copy + Ord> ColumnFrequencies<T> {
+    fn analyze<I, R>(records: I) -> Result<ColumnFrequencies<T>>
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator<Item = T>,
+    {
+        let mut n_records = 0;
+        let mut columns: Vec<HashMap<T, usize>> = vec![];
+        for record in records {
+            let mut width = 0;
+            for (i, e) in record.into_iter().enumerate() {
+                if n_records == 0 {
+                    columns.push(HashMap::new());
+                }
+                *columns
+                    .get_mut(i)
+                    .ok_or(anyhow!("record {} is too long", n_records))?
+                    .entry(e)
+                    .or_insert(0) += 1;
+                width += 1;
+            }
+            if width != columns.len() {
+                return Err(anyhow!("record {} is too short", n_records));
+            }
+            n_records += 1;
+        }
+        if n_records == 0 {
+            return Err(anyhow!("empty input!"));
+        }
+        Ok(ColumnFrequencies {
+            records: n_records,
+            columns,
+        })
+    }
+
+    fn histogram(&self, column: usize) -> Vec<(T, usize)> {
+        let mut histogram: Vec<(T, usize)> = self
+            .columns
+            .get(column)
+            .map(|c| c.iter().map(|(&e, &n)| (e, n)).collect())
+            .unwrap_or_default();
+        histogram.sort_unstable();
+        histogram
+    }
+
+    fn most_common(&self) -> Vec<Vec<T>> {
+        self.columns
+            .iter()
+            .filter_map(|c| select_by_count(c, |n1, n2| n1 > n2))
+            .collect()
+    }
+
+    fn least_common(&self) -> Vec<Vec<T>> {
+        self.columns
+            .iter()
+            .filter_map(|c| select_by_count(c, |n1, n2| n1 < n2))
+            .collect()
+    }
+
+    fn consensus(&self) -> Vec<Option<T>> {
+        self.most_common()
+            .into_iter()
+            .map(|c| {
+                if c.len() == 1 {
+                    c.first().copied()
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn entropy(&self) -> Vec<f64> {
+        self.columns
+            .iter()
+            .map(|c| {
+                c.values()
+                    .map(|&n| {
+                        let p = n as f64 / self.records as f64;
+                        -p * p.log2()
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{least_common_chars, least_common_string, most_common_chars, most_common_string};
+    use crate::{
+        least_common_chars, least_common_string, most_common_chars, most_common_string,
+        ColumnFrequencies, Report, TiePolicy,
+    };
     use anyhow::Result;
 
     const NUMS: [&str; 12] = [
@@ -152,17 +585,130 @@ mod tests {
 
     #[test]
     fn test_least_and_most_common_chars() -> Result<()> {
-        let nums = NUMS.to_vec().iter().map(|s| s.to_string()).collect();
-        assert_eq!(most_common_chars(&nums)?, "10110");
-        assert_eq!(least_common_chars(&nums)?, "01001");
+        let nums: Vec<String> = NUMS.iter().map(|s| s.to_string()).collect();
+        assert_eq!(most_common_chars(&nums, TiePolicy::Error)?, ["10110"]);
+        assert_eq!(least_common_chars(&nums, TiePolicy::Error)?, ["01001"]);
         Ok(())
     }
 
     #[test]
     fn test_least_and_most_common_strings() -> Result<()> {
-        let nums = NUMS.to_vec().iter().map(|s| s.to_string()).collect();
-        assert_eq!(most_common_string(&nums)?, "10111");
-        assert_eq!(least_common_string(&nums)?, "01010");
+        let nums: Vec<String> = NUMS.iter().map(|s| s.to_string()).collect();
+        assert_eq!(most_common_string(&nums, TiePolicy::PreferOne)?, ["10111"]);
+        assert_eq!(
+            least_common_string(&nums, TiePolicy::PreferZero)?,
+            ["01010"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_tie_policies() -> Result<()> {
+        let nums: Vec<String> = ["110", "011", "100", "001"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert!(most_common_chars(&nums, TiePolicy::Error).is_err());
+        assert_eq!(most_common_chars(&nums, TiePolicy::PreferOne)?, ["111"]);
+        assert_eq!(least_common_chars(&nums, TiePolicy::PreferZero)?, ["000"]);
+        assert_eq!(most_common_chars(&nums, TiePolicy::KeepBoth)?.len(), 8);
+
+        assert!(most_common_string(&nums, TiePolicy::Error).is_err());
+        assert_eq!(most_common_string(&nums, TiePolicy::PreferOne)?, ["110"]);
+        assert_eq!(most_common_string(&nums, TiePolicy::PreferZero)?, ["001"]);
+        assert_eq!(
+            most_common_string(&nums, TiePolicy::KeepBoth)?,
+            ["001", "011", "100", "110"]
+        );
+
+        let report = Report::parse(&nums)?;
+        assert!(report.gamma_rate(TiePolicy::Error).is_err());
+        assert_eq!(report.gamma_rate(TiePolicy::PreferOne)?, [0b111]);
+        assert_eq!(report.epsilon_rate(TiePolicy::KeepBoth)?.len(), 8);
+        assert_eq!(
+            report.oxigen_generator_rating(TiePolicy::KeepBoth)?,
+            [0b001, 0b011, 0b100, 0b110]
+        );
+        assert_eq!(report.co2_scrubber_rating(TiePolicy::PreferZero)?, [0b001]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_report() -> Result<()> {
+        let report = Report::parse(&NUMS)?;
+        assert_eq!(report.gamma_rate(TiePolicy::Error)?, [0b10110]);
+        assert_eq!(report.epsilon_rate(TiePolicy::Error)?, [0b01001]);
+        assert_eq!(
+            report.oxigen_generator_rating(TiePolicy::PreferOne)?,
+            [0b10111]
+        );
+        assert_eq!(
+            report.co2_scrubber_rating(TiePolicy::PreferZero)?,
+            [0b01010]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_report_matches_strings() -> Result<()> {
+        let inputs: [&[&str]; 3] = [
+            &["110", "011", "101", "111"],
+            &["0011", "0101", "0110", "0111", "1111"],
+            &["10", "11", "01"],
+        ];
+        for input in inputs {
+            let strings: Vec<String> = input.iter().map(|s| s.to_string()).collect();
+            let report = Report::parse(input)?;
+            for tie in [
+                TiePolicy::PreferOne,
+                TiePolicy::PreferZero,
+                TiePolicy::KeepBoth,
+            ] {
+                let to_bits = |strings: Vec<String>| {
+                    strings
+                        .iter()
+                        .map(|s| u64::from_str_radix(s, 2))
+                        .collect::<Result<Vec<u64>, _>>()
+                };
+                assert_eq!(
+                    report.oxigen_generator_rating(tie)?,
+                    to_bits(most_common_string(&strings, tie)?)?
+                );
+                assert_eq!(
+                    report.co2_scrubber_rating(tie)?,
+                    to_bits(least_common_string(&strings, tie)?)?
+                );
+            }
+        }
+        assert!(Report::parse(&["101", "10"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_column_frequencies() -> Result<()> {
+        let dna = ["ACGT", "ACGA", "TCGA", "ACCA"];
+        let frequencies = ColumnFrequencies::analyze(dna.iter().map(|s| s.chars()))?;
+        assert_eq!(frequencies.histogram(0), [('A', 3), ('T', 1)]);
+        assert_eq!(frequencies.histogram(2), [('C', 1), ('G', 3)]);
+        assert_eq!(
+            frequencies.consensus(),
+            [Some('A'), Some('C'), Some('G'), Some('A')]
+        );
+        let entropy = frequencies.entropy();
+        assert_eq!(entropy[1], 0.0);
+        assert!((entropy[0] - 0.8113).abs() < 1e-4);
+
+        let hex = ["ff", "f0", "0f"];
+        let frequencies = ColumnFrequencies::analyze(hex.iter().map(|s| s.chars()))?;
+        assert_eq!(frequencies.least_common(), [vec!['0'], vec!['0']]);
+        assert_eq!(frequencies.entropy()[0], frequencies.entropy()[1]);
+
+        let tied = ["ab", "ba"];
+        let frequencies = ColumnFrequencies::analyze(tied.iter().map(|s| s.chars()))?;
+        assert_eq!(frequencies.consensus(), [None, None]);
+
+        assert!(ColumnFrequencies::analyze(["abc", "ab"].iter().map(|s| s.chars())).is_err());
+        assert!(ColumnFrequencies::analyze(["ab", "abc"].iter().map(|s| s.chars())).is_err());
         Ok(())
     }
 }
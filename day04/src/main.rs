@@ -1,22 +1,163 @@
 use anyhow::{anyhow, Result};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
+use std::str::FromStr;
 
 fn main() -> Result<()> {
     let input = read_input("input.txt")?;
+    let args: Vec<String> = std::env::args().collect();
+    let patterns = match args.iter().position(|a| a == "--patterns") {
+        Some(i) => parse_patterns(args.get(i + 1).ok_or(anyhow!("missing patterns"))?)?,
+        None => Pattern::standard(),
+    };
+
+    let mut game = Game::parse(&input)?.with_patterns(patterns);
+    let seed = match args.iter().position(|a| a == "--seed") {
+        Some(i) => args.get(i + 1).ok_or(anyhow!("missing seed"))?.parse()?,
+        None => 0,
+    };
+    if let Some(i) = args.iter().position(|a| a == "--generate") {
+        let n: usize = args
+            .get(i + 1)
+            .ok_or(anyhow!("missing board count"))?
+            .parse()?;
+        let mut rng = StdRng::seed_from_u64(seed);
+        game.boards = (0..n).map(|_| Board::generate(&mut rng, 5, 100)).collect();
+        game.numbers = (0..100).collect();
+    }
+    if let Some(i) = args.iter().position(|a| a == "--monte-carlo") {
+        let runs: usize = args
+            .get(i + 1)
+            .ok_or(anyhow!("missing run count"))?
+            .parse()?;
+        for (b, stats) in monte_carlo(&game, runs, seed).iter().enumerate() {
+            println!(
+                "{} {:.4} {:.4} {}",
+                b,
+                stats.first_wins / runs as f64,
+                stats.last_wins / runs as f64,
+                stats
+                    .expected_turn()
+                    .map(|t| format!("{:.2}", t))
+                    .unwrap_or("-".to_string())
+            );
+        }
+        return Ok(());
+    }
+
+    let timeline = if args.iter().any(|a| a == "--indexed") {
+        IndexedGame::new(&game).simulate()
+    } else {
+        game.simulate()
+    };
+    if args.iter().any(|a| a == "--json") {
+        println!("{}", timeline.to_json());
+        return Ok(());
+    }
 
-    let mut game1 = Game::parse(&input)?;
-    let result1 = game1.play().ok_or(anyhow!("no board wins!"))?;
-    println!("{}", result1);
+    let result1 = timeline.first_winner().ok_or(anyhow!("no board wins!"))?;
+    println!("{}", result1.score);
 
-    let mut game2 = Game::parse(&input)?;
-    let result2 = game2.play_to_lose().ok_or(anyhow!("no board wins!"))?;
-    println!("{}", result2);
+    let result2 = timeline.last_winner().ok_or(anyhow!("no board wins!"))?;
+    println!("{}", result2.score);
 
     Ok(())
 }
 
+#[derive(Clone, Debug, PartialEq)]
+enum Pattern {
+    Rows,
+    Columns,
+    Diagonals,
+    FourCorners,
+    Blackout,
+    Mask(Vec<(usize, usize)>),
+}
+
+impl Pattern {
+    fn standard() -> Vec<Pattern> {
+        vec![Pattern::Rows, Pattern::Columns]
+    }
+
+    fn lines(&self, height: usize, width: usize) -> Vec<Vec<(usize, usize)>> {
+        match self {
+            Pattern::Rows => (0..height)
+                .map(|y| (0..width).map(|x| (y, x)).collect())
+                .collect(),
+            Pattern::Columns => (0..width)
+                .map(|x| (0..height).map(|y| (y, x)).collect())
+                .collect(),
+            Pattern::Diagonals => {
+                if height != width {
+                    return vec![];
+                }
+                vec![
+                    (0..height).map(|i| (i, i)).collect(),
+                    (0..height).map(|i| (i, width - 1 - i)).collect(),
+                ]
+            }
+            Pattern::FourCorners => {
+                if height == 0 || width == 0 {
+                    return vec![];
+                }
+                vec![vec![
+                    (0, 0),
+                    (0, width - 1),
+                    (height - 1, 0),
+                    (height - 1, width - 1),
+                ]]
+            }
+            Pattern::Blackout => vec![(0..height)
+                .flat_map(|y| (0..width).map(move |x| (y, x)))
+                .collect()],
+            Pattern::Mask(cells) => {
+                if !cells.is_empty() && cells.iter().all(|&(y, x)| y < height && x < width) {
+                    vec![cells.clone()]
+                } else {
+                    vec![]
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "rows" => Ok(Pattern::Rows),
+            "columns" => Ok(Pattern::Columns),
+            "diagonals" => Ok(Pattern::Diagonals),
+            "corners" => Ok(Pattern::FourCorners),
+            "blackout" => Ok(Pattern::Blackout),
+            _ => {
+                let cells = s
+                    .strip_prefix("mask:")
+                    .ok_or(anyhow!("unknown pattern: {:?}", s))?
+                    .split('/')
+                    .map(|c| {
+                        let (y, x) = c
+                            .split_once('-')
+                            .ok_or(anyhow!("invalid mask cell: {:?}", c))?;
+                        Ok((y.parse()?, x.parse()?))
+                    })
+                    .collect::<Result<Vec<(usize, usize)>>>()?;
+                Ok(Pattern::Mask(cells))
+            }
+        }
+    }
+}
+
+fn parse_patterns(input: &str) -> Result<Vec<Pattern>> {
+    input.split(',').map(|p| p.parse()).collect()
+}
+
 #[derive(Clone, Debug)]
 struct Number {
     value: i32,
@@ -46,27 +187,36 @@ impl Board {
         }
     }
 
-    fn play<'a, I: IntoIterator<Item = &'a i32>>(&mut self, numbers: I) -> Option<usize> {
+    fn generate<R: Rng>(rng: &mut R, size: usize, max_value: i32) -> Board {
+        let values: Vec<i32> = (0..max_value).collect();
+        let mut cells = values.choose_multiple(rng, size * size);
+        Board::new(
+            (0..size)
+                .map(|_| cells.by_ref().take(size).map(|&v| Number::new(v)).collect())
+                .collect(),
+        )
+    }
+
+    fn play<'a, I: IntoIterator<Item = &'a i32>>(
+        &mut self,
+        numbers: I,
+        patterns: &[Pattern],
+    ) -> Option<usize> {
+        let height = self.rows.len();
+        let width = self.rows.first()?.len();
+        let lines: Vec<Vec<(usize, usize)>> = patterns
+            .iter()
+            .flat_map(|p| p.lines(height, width))
+            .collect();
+
         for (i, n) in numbers.into_iter().enumerate() {
             self.last_number = *n;
             for r in self.rows.iter_mut() {
                 mark_row(r, *n);
             }
 
-            for r in &self.rows {
-                if r.iter().all(|n| n.marked) {
-                    return Some(i);
-                }
-            }
-
-            for x in 0..self.rows.first()?.len() - 1 {
-                let mut all = true;
-                for r in &self.rows {
-                    if !r.get(x)?.marked {
-                        all = false;
-                    }
-                }
-                if all {
+            for line in &lines {
+                if line.iter().all(|&(y, x)| self.is_marked(y, x)) {
                     return Some(i);
                 }
             }
@@ -74,17 +224,25 @@ impl Board {
         None
     }
 
+    fn is_marked(&self, y: usize, x: usize) -> bool {
+        self.rows
+            .get(y)
+            .and_then(|r| r.get(x))
+            .map(|n| n.marked)
+            .unwrap_or(false)
+    }
+
     fn score(&self) -> i32 {
         self.last_number
             * self
                 .rows
                 .iter()
                 .map(|r| r.iter().filter(|n| !n.marked).fold(0, |s, n| s + n.value))
-                .fold(0, |s1, s2| s1 + s2)
+                .sum::<i32>()
     }
 }
 
-fn mark_row(row: &mut Vec<Number>, n: i32) {
+fn mark_row(row: &mut [Number], n: i32) {
     for number in row.iter_mut() {
         if number.value == n {
             number.marked = true;
@@ -96,6 +254,7 @@ fn mark_row(row: &mut Vec<Number>, n: i32) {
 struct Game {
     numbers: Vec<i32>,
     boards: Vec<Board>,
+    patterns: Vec<Pattern>,
 }
 
 impl Game {
@@ -119,48 +278,281 @@ impl Game {
             boards.push(Board::new(rows));
         }
 
-        Ok(Game { numbers, boards })
+        Ok(Game {
+            numbers,
+            boards,
+            patterns: Pattern::standard(),
+        })
+    }
+
+    fn with_patterns(mut self, patterns: Vec<Pattern>) -> Self {
+        self.patterns = patterns;
+        self
+    }
+
+    fn simulate(&self) -> Timeline {
+        let mut wins = vec![];
+        let mut never_won = vec![];
+
+        for (i, b) in self.boards.iter().enumerate() {
+            let mut board = b.clone();
+            match board.play(&self.numbers, &self.patterns) {
+                Some(n) => wins.push(Win {
+                    board: i,
+                    draw: n,
+                    number: board.last_number,
+                    score: board.score(),
+                }),
+                None => never_won.push(i),
+            }
+        }
+        wins.sort_by_key(|w| (w.draw, w.board));
+
+        Timeline { wins, never_won }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct IndexedBoard {
+    marked: Vec<bool>,
+    cell_lines: Vec<Vec<usize>>,
+    missing: Vec<usize>,
+    unmarked_sum: i32,
+}
+
+impl IndexedBoard {
+    fn mark(&mut self, cell: usize, value: i32) -> bool {
+        if self.marked[cell] {
+            return false;
+        }
+        self.marked[cell] = true;
+        self.unmarked_sum -= value;
+        let mut complete = false;
+        for &l in &self.cell_lines[cell] {
+            self.missing[l] -= 1;
+            complete |= self.missing[l] == 0;
+        }
+        complete
     }
+}
+
+#[derive(Debug)]
+struct IndexedGame {
+    numbers: Vec<i32>,
+    index: HashMap<i32, Vec<(usize, Vec<usize>)>>,
+    boards: Vec<IndexedBoard>,
+}
+
+impl IndexedGame {
+    fn new(game: &Game) -> IndexedGame {
+        let mut index: HashMap<i32, Vec<(usize, Vec<usize>)>> = HashMap::new();
+        let mut boards = vec![];
 
-    fn play(&mut self) -> Option<i32> {
-        let mut winning_board = None;
-        let mut winning_n = usize::MAX;
+        for (b, board) in game.boards.iter().enumerate() {
+            let height = board.rows.len();
+            let width = board.rows.first().map(|r| r.len()).unwrap_or(0);
+            let lines: Vec<Vec<(usize, usize)>> = game
+                .patterns
+                .iter()
+                .flat_map(|p| p.lines(height, width))
+                .collect();
+
+            let mut cell_lines = vec![vec![]; height * width];
+            for (l, line) in lines.iter().enumerate() {
+                for &(y, x) in line {
+                    cell_lines[y * width + x].push(l);
+                }
+            }
 
-        for b in self.boards.iter_mut() {
-            if let Some(n) = b.play(&self.numbers) {
-                if n < winning_n {
-                    winning_n = n;
-                    winning_board = Some(b);
+            let mut marked = vec![];
+            let mut unmarked_sum = 0;
+            for (y, row) in board.rows.iter().enumerate() {
+                for (x, n) in row.iter().enumerate() {
+                    let cell = y * width + x;
+                    marked.push(n.marked);
+                    if !n.marked {
+                        unmarked_sum += n.value;
+                    }
+                    let entries = index.entry(n.value).or_default();
+                    match entries.last_mut() {
+                        Some((last, cells)) if *last == b => cells.push(cell),
+                        _ => entries.push((b, vec![cell])),
+                    }
                 }
             }
+
+            let missing = lines
+                .iter()
+                .map(|line| {
+                    line.iter()
+                        .filter(|&&(y, x)| !marked[y * width + x])
+                        .count()
+                })
+                .collect();
+
+            boards.push(IndexedBoard {
+                marked,
+                cell_lines,
+                missing,
+                unmarked_sum,
+            });
         }
 
-        winning_board.map(|b| b.score())
+        IndexedGame {
+            numbers: game.numbers.clone(),
+            index,
+            boards,
+        }
+    }
+
+    fn simulate(&self) -> Timeline {
+        self.simulate_draws(&self.numbers)
     }
 
-    fn play_to_lose(&mut self) -> Option<i32> {
-        let mut losing_board = None;
-        let mut losing_n = 0;
+    fn simulate_draws(&self, numbers: &[i32]) -> Timeline {
+        let mut boards = self.boards.clone();
+        let mut won = vec![false; boards.len()];
+        let mut wins = vec![];
 
-        for b in self.boards.iter_mut() {
-            if let Some(n) = b.play(&self.numbers) {
-                if n > losing_n {
-                    losing_n = n;
-                    losing_board = Some(b);
+        for (d, n) in numbers.iter().enumerate() {
+            if wins.len() == boards.len() {
+                break;
+            }
+            for (b, cells) in self.index.get(n).into_iter().flatten() {
+                if won[*b] {
+                    continue;
+                }
+                let mut complete = false;
+                for &cell in cells {
+                    complete |= boards[*b].mark(cell, *n);
+                }
+                if complete {
+                    won[*b] = true;
+                    wins.push(Win {
+                        board: *b,
+                        draw: d,
+                        number: *n,
+                        score: boards[*b].unmarked_sum * n,
+                    });
                 }
             }
         }
+        wins.sort_by_key(|w| (w.draw, w.board));
+        let never_won = (0..boards.len()).filter(|&b| !won[b]).collect();
+
+        Timeline { wins, never_won }
+    }
+}
+
+/// Per-board results over many shuffled games. Boards that tie for first or
+/// last place share that game's credit equally.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct BoardStats {
+    first_wins: f64,
+    last_wins: f64,
+    wins: usize,
+    total_turns: usize,
+}
+
+impl BoardStats {
+    fn expected_turn(&self) -> Option<f64> {
+        if self.wins == 0 {
+            return None;
+        }
+        Some(self.total_turns as f64 / self.wins as f64)
+    }
+}
+
+fn monte_carlo(game: &Game, runs: usize, seed: u64) -> Vec<BoardStats> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let indexed = IndexedGame::new(game);
+    let mut numbers = game.numbers.clone();
+    let mut stats = vec![BoardStats::default(); game.boards.len()];
+
+    for _ in 0..runs {
+        numbers.shuffle(&mut rng);
+        let timeline = indexed.simulate_draws(&numbers);
+        for w in &timeline.wins {
+            stats[w.board].wins += 1;
+            stats[w.board].total_turns += w.draw + 1;
+        }
+        let tied = |draw: usize| -> Vec<usize> {
+            timeline
+                .wins
+                .iter()
+                .filter(|w| w.draw == draw)
+                .map(|w| w.board)
+                .collect()
+        };
+        if let Some(w) = timeline.first_winner() {
+            let boards = tied(w.draw);
+            for &b in &boards {
+                stats[b].first_wins += 1.0 / boards.len() as f64;
+            }
+        }
+        if let Some(w) = timeline.last_winner() {
+            let boards = tied(w.draw);
+            for &b in &boards {
+                stats[b].last_wins += 1.0 / boards.len() as f64;
+            }
+        }
+    }
+
+    stats
+}
 
-        losing_board.map(|b| b.score())
+#[derive(Clone, Debug, PartialEq)]
+struct Win {
+    board: usize,
+    draw: usize,
+    number: i32,
+    score: i32,
+}
+
+#[derive(Debug)]
+struct Timeline {
+    wins: Vec<Win>,
+    never_won: Vec<usize>,
+}
+
+impl Timeline {
+    fn first_winner(&self) -> Option<&Win> {
+        self.wins.first()
+    }
+
+    fn last_winner(&self) -> Option<&Win> {
+        let last_draw = self.wins.last()?.draw;
+        self.wins.iter().find(|w| w.draw == last_draw)
+    }
+
+    fn to_json(&self) -> String {
+        let wins = self
+            .wins
+            .iter()
+            .map(|w| {
+                format!(
+                    "{{\"board\":{},\"draw\":{},\"number\":{},\"score\":{}}}",
+                    w.board, w.draw, w.number, w.score
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(",");
+        let never_won = self
+            .never_won
+            .iter()
+            .map(|b| b.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        format!("{{\"wins\":[{}],\"never_won\":[{}]}}", wins, never_won)
     }
 }
 
-fn parse_line(input: &String) -> Result<Vec<Number>> {
-    input.split_whitespace().map(|n| parse_number(n)).collect()
+fn parse_line(input: &str) -> Result<Vec<Number>> {
+    input.split_whitespace().map(parse_number).collect()
 }
 
-fn parse_numbers(input: &String) -> Result<Vec<i32>> {
-    input.split(',').map(|n| parse_i32(n)).collect()
+fn parse_numbers(input: &str) -> Result<Vec<i32>> {
+    input.split(',').map(parse_i32).collect()
 }
 
 fn parse_number(input: &str) -> Result<Number> {
@@ -180,7 +572,10 @@ fn read_input<P: AsRef<std::path::Path>>(path: P) -> Result<Vec<String>> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Board, Game, Number};
+    use crate::{monte_carlo, Board, Game, IndexedGame, Number, Pattern, Win};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::collections::HashSet;
 
     #[test]
     fn test_game() {
@@ -301,11 +696,191 @@ mod tests {
                 ],
             ]),
         ];
-        let mut game = Game {
+        let game = Game {
             numbers: nums,
             boards,
+            patterns: Pattern::standard(),
+        };
+
+        let timeline = game.simulate();
+        assert_eq!(timeline.first_winner().map(|w| w.score), Some(4512));
+        assert_eq!(timeline.last_winner().map(|w| w.score), Some(1924));
+        assert_eq!(
+            timeline.wins,
+            [
+                Win {
+                    board: 2,
+                    draw: 11,
+                    number: 24,
+                    score: 4512
+                },
+                Win {
+                    board: 0,
+                    draw: 13,
+                    number: 16,
+                    score: 2192
+                },
+                Win {
+                    board: 1,
+                    draw: 14,
+                    number: 13,
+                    score: 1924
+                },
+            ]
+        );
+        assert!(timeline.never_won.is_empty());
+
+        let indexed = IndexedGame::new(&game).simulate();
+        assert_eq!(indexed.wins, timeline.wins);
+        assert_eq!(indexed.never_won, timeline.never_won);
+    }
+
+    #[test]
+    fn test_indexed_game() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let all_patterns = [
+            Pattern::standard(),
+            vec![Pattern::Diagonals, Pattern::FourCorners],
+            vec![Pattern::Blackout],
+            vec![Pattern::Mask(vec![(0, 0), (2, 3), (4, 4)])],
+        ];
+        for patterns in all_patterns {
+            let boards = (0..200)
+                .map(|_| {
+                    Board::new(
+                        (0..5)
+                            .map(|_| (0..5).map(|_| Number::new(rng.gen_range(0..60))).collect())
+                            .collect(),
+                    )
+                })
+                .collect();
+            let numbers = (0..80).map(|_| rng.gen_range(0..60)).collect();
+            let game = Game {
+                numbers,
+                boards,
+                patterns,
+            };
+            let timeline = game.simulate();
+            let indexed = IndexedGame::new(&game).simulate();
+            assert_eq!(indexed.wins, timeline.wins);
+            assert_eq!(indexed.never_won, timeline.never_won);
+        }
+    }
+
+    #[test]
+    fn test_timeline() {
+        let game = Game {
+            numbers: vec![1, 2, 3, 4, 5, 6],
+            boards: vec![
+                board([[1, 2, 3], [4, 5, 6], [7, 8, 9]]),
+                board([[4, 5, 6], [7, 8, 9], [10, 11, 12]]),
+                board([[1, 5, 9], [20, 6, 10], [3, 7, 11]]),
+            ],
+            patterns: Pattern::standard(),
         };
+        let timeline = game.simulate();
+        assert_eq!(timeline.first_winner().map(|w| w.board), Some(0));
+        assert_eq!(timeline.last_winner().map(|w| w.board), Some(1));
+        assert_eq!(timeline.never_won, [2]);
+        assert_eq!(
+            timeline.to_json(),
+            concat!(
+                r#"{"wins":[{"board":0,"draw":2,"number":3,"score":117},"#,
+                r#"{"board":1,"draw":5,"number":6,"score":342}],"never_won":[2]}"#
+            )
+        );
+    }
+
+    fn board(values: [[i32; 3]; 3]) -> Board {
+        Board::new(
+            values
+                .iter()
+                .map(|r| r.iter().map(|&v| Number::new(v)).collect())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_patterns() {
+        let values = [[1, 2, 3], [4, 5, 6], [7, 8, 9]];
+
+        assert_eq!(
+            board(values).play(&[3, 6, 9], &Pattern::standard()),
+            Some(2)
+        );
+        assert_eq!(board(values).play(&[1, 5, 9], &Pattern::standard()), None);
+        assert_eq!(
+            board(values).play(&[1, 5, 9], &[Pattern::Diagonals]),
+            Some(2)
+        );
+        assert_eq!(
+            board(values).play(&[7, 5, 3], &[Pattern::Diagonals]),
+            Some(2)
+        );
+        assert_eq!(
+            board(values).play(&[1, 2, 3, 7, 9], &[Pattern::FourCorners]),
+            Some(4)
+        );
+        assert_eq!(
+            board(values).play(&[1, 2, 3, 4, 5, 6, 7, 8], &[Pattern::Blackout]),
+            None
+        );
+        assert_eq!(
+            board(values).play(&[1, 2, 3, 4, 5, 6, 7, 8, 9], &[Pattern::Blackout]),
+            Some(8)
+        );
+
+        let mask: Pattern = "mask:0-1/1-0/1-2/2-1".parse().unwrap();
+        assert_eq!(mask, Pattern::Mask(vec![(0, 1), (1, 0), (1, 2), (2, 1)]));
+        assert_eq!(board(values).play(&[2, 4, 6, 1, 8], &[mask]), Some(4));
+        assert!("mask:0-x".parse::<Pattern>().is_err());
+        assert!("triangle".parse::<Pattern>().is_err());
+    }
+
+    #[test]
+    fn test_generate() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let board = Board::generate(&mut rng, 5, 100);
+        assert_eq!(board.rows.len(), 5);
+        assert!(board.rows.iter().all(|r| r.len() == 5));
+        let values: HashSet<i32> = board.rows.iter().flatten().map(|n| n.value).collect();
+        assert_eq!(values.len(), 25);
+        assert!(values.iter().all(|&v| (0..100).contains(&v)));
+    }
 
-        assert_eq!(game.play(), Some(4512));
+    #[test]
+    fn test_monte_carlo() {
+        let game = Game {
+            numbers: (1..=9).collect(),
+            boards: vec![
+                board([[1, 2, 3], [4, 5, 6], [7, 8, 9]]),
+                board([[1, 2, 3], [4, 5, 6], [7, 8, 9]]),
+                board([[1, 2, 3], [4, 5, 6], [70, 80, 90]]),
+            ],
+            patterns: vec![Pattern::Blackout],
+        };
+        let stats = monte_carlo(&game, 100, 1);
+        assert_eq!(stats, monte_carlo(&game, 100, 1));
+        // The two identical boards always tie, so they split every game.
+        assert!((stats[0].first_wins - 50.0).abs() < 1e-9);
+        assert!((stats[0].last_wins - 50.0).abs() < 1e-9);
+        assert_eq!(stats[0].first_wins, stats[1].first_wins);
+        assert_eq!(stats[0].last_wins, stats[1].last_wins);
+        assert_eq!(stats[2].first_wins, 0.0);
+        assert_eq!(stats[0].expected_turn(), Some(9.0));
+        assert_eq!(stats[1].expected_turn(), Some(9.0));
+        assert_eq!(stats[2].expected_turn(), None);
+
+        let game = Game {
+            numbers: (1..=9).collect(),
+            boards: vec![
+                board([[1, 2, 3], [4, 5, 6], [7, 8, 9]]),
+                board([[1, 20, 30], [2, 50, 60], [3, 80, 90]]),
+            ],
+            patterns: Pattern::standard(),
+        };
+        let stats = monte_carlo(&game, 1000, 2);
+        assert!(stats[0].first_wins > stats[1].first_wins);
+        assert!((stats[0].first_wins + stats[1].first_wins - 1000.0).abs() < 1e-9);
     }
 }
@@ -1,24 +1,174 @@
 use anyhow::{anyhow, Result};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
+use std::str::FromStr;
 
 fn main() -> Result<()> {
     let input = read_input("input.txt")?;
     let segments = parse_segments(&input)?;
 
-    let result = solve(&segments);
+    let args: Vec<String> = std::env::args().collect();
+    let rasterization = if args.iter().any(|a| a == "--any-angle") {
+        Rasterization::Bresenham
+    } else {
+        Rasterization::Strict
+    };
+
+    let sweep = args.iter().any(|a| a == "--sweep");
+    if sweep && rasterization == Rasterization::Bresenham {
+        return Err(anyhow!("--sweep does not support --any-angle"));
+    }
+
+    let ppm = args.iter().position(|a| a == "--ppm");
+    let ascii = args.iter().any(|a| a == "--ascii");
+    let render = ascii || ppm.is_some();
+    let filter = match args.iter().position(|a| a == "--only") {
+        Some(i) => args.get(i + 1).ok_or(anyhow!("missing filter"))?.parse()?,
+        None => SegmentFilter::All,
+    };
+
+    // The per-point map is only built when a query or an unfiltered render
+    // needs it; `--sweep` on its own counts overlaps without rasterizing.
+    let queries = ["--threshold", "--densest", "--through", "--intersections"];
+    let map = (args.iter().any(|a| queries.contains(&a.as_str()))
+        || (render && filter == SegmentFilter::All))
+        .then(|| DensityMap::build(&segments, rasterization));
+    let result = match &map {
+        _ if sweep => count_overlaps(&segments),
+        Some(map) => map.count_at_least(2),
+        None => solve(&segments, rasterization),
+    };
     println!("{}", result);
+
+    if let Some(map) = &map {
+        if let Some(i) = args.iter().position(|a| a == "--threshold") {
+            let k: usize = args
+                .get(i + 1)
+                .ok_or(anyhow!("missing threshold"))?
+                .parse()?;
+            println!("density >= {}: {}", k, map.count_at_least(k));
+        }
+        if args.iter().any(|a| a == "--densest") {
+            let (max, points) = map.densest();
+            println!("densest ({}): {:?}", max, points);
+        }
+        if let Some(i) = args.iter().position(|a| a == "--through") {
+            let point = parse_point(args.get(i + 1).ok_or(anyhow!("missing point"))?)?;
+            println!(
+                "segments through {:?} (density {}): {:?}",
+                point,
+                map.density(&point),
+                map.segments_through(&point)
+            );
+        }
+        if args.iter().any(|a| a == "--intersections") {
+            for (s1, s2, points) in map.intersections() {
+                println!("{} x {}: {:?}", s1, s2, points);
+            }
+        }
+    }
+
+    if !render {
+        return Ok(());
+    }
+
+    let density = match map {
+        Some(map) if filter == SegmentFilter::All => map.counts(),
+        _ => {
+            let visible: Vec<&Segment> = segments.iter().filter(|s| filter.matches(s)).collect();
+            DensityMap::build(visible, rasterization).counts()
+        }
+    };
+    let viewport = match args.iter().position(|a| a == "--viewport") {
+        Some(i) => args
+            .get(i + 1)
+            .ok_or(anyhow!("missing viewport"))?
+            .parse()?,
+        None => Viewport::fit(&density).unwrap_or(Viewport {
+            min_x: 0,
+            min_y: 0,
+            max_x: 0,
+            max_y: 0,
+        }),
+    };
+    let scale = match args.iter().position(|a| a == "--scale") {
+        Some(i) => args.get(i + 1).ok_or(anyhow!("missing scale"))?.parse()?,
+        None => 1,
+    };
+    if ascii {
+        print!("{}", render_ascii(&density, &viewport, scale));
+    }
+    if let Some(i) = ppm {
+        let path = args.get(i + 1).ok_or(anyhow!("missing ppm path"))?;
+        File::create(path)?.write_all(&render_ppm(&density, &viewport, scale))?;
+    }
     Ok(())
 }
 
-#[derive(Eq, PartialEq, Debug, Hash)]
+#[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Hash, Clone, Copy)]
 struct Point {
     x: i32,
     y: i32,
 }
 
+type Interval = (i64, i64);
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+enum Direction {
+    Horizontal,
+    Vertical,
+    Diagonal,
+    AntiDiagonal,
+}
+
+impl Direction {
+    fn line_through(&self, x: i64, y: i64) -> (i64, i64) {
+        match self {
+            Direction::Horizontal => (y, x),
+            Direction::Vertical => (x, y),
+            Direction::Diagonal => (y - x, x),
+            Direction::AntiDiagonal => (y + x, x),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+struct Line {
+    direction: Direction,
+    offset: i64,
+}
+
+impl Line {
+    fn intersection(&self, other: &Line) -> Option<(i64, i64)> {
+        use Direction::*;
+        let (c1, c2) = (self.offset, other.offset);
+        let (x, y) = match (self.direction, other.direction) {
+            (Horizontal, Vertical) => (c2, c1),
+            (Horizontal, Diagonal) => (c1 - c2, c1),
+            (Horizontal, AntiDiagonal) => (c2 - c1, c1),
+            (Vertical, Diagonal) => (c1, c1 + c2),
+            (Vertical, AntiDiagonal) => (c1, c2 - c1),
+            (Diagonal, AntiDiagonal) => {
+                if (c1 + c2) % 2 != 0 {
+                    return None;
+                }
+                ((c2 - c1) / 2, (c1 + c2) / 2)
+            }
+            (d1, d2) if d1 == d2 => return None,
+            _ => return other.intersection(self),
+        };
+        Some((x, y))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Rasterization {
+    Strict,
+    Bresenham,
+}
+
 #[derive(Debug)]
 struct Segment {
     from: Point,
@@ -26,52 +176,337 @@ struct Segment {
 }
 
 impl Segment {
-    fn points(&self) -> Vec<Point> {
-        let xs: Vec<i32> = if self.from.x < self.to.x {
-            (self.from.x..self.to.x + 1).collect()
-        } else {
-            (self.to.x..self.from.x + 1).rev().collect()
-        };
-        let ys: Vec<i32> = if self.from.y < self.to.y {
-            (self.from.y..self.to.y + 1).collect()
+    fn points(&self, rasterization: Rasterization) -> Vec<Point> {
+        if rasterization == Rasterization::Strict && self.line().is_none() {
+            return vec![];
+        }
+
+        let (x1, y1) = (self.to.x, self.to.y);
+        let dx = (x1 - self.from.x).abs();
+        let dy = -(y1 - self.from.y).abs();
+        let sx = if self.from.x < x1 { 1 } else { -1 };
+        let sy = if self.from.y < y1 { 1 } else { -1 };
+        let (mut x, mut y) = (self.from.x, self.from.y);
+        let mut err = dx + dy;
+        let mut points = vec![];
+        loop {
+            points.push(Point { x, y });
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+        points
+    }
+
+    fn line(&self) -> Option<(Line, i64, i64)> {
+        let (x1, y1) = (self.from.x as i64, self.from.y as i64);
+        let (x2, y2) = (self.to.x as i64, self.to.y as i64);
+        let direction = if y1 == y2 {
+            Direction::Horizontal
+        } else if x1 == x2 {
+            Direction::Vertical
+        } else if x2 - x1 == y2 - y1 {
+            Direction::Diagonal
+        } else if x2 - x1 == y1 - y2 {
+            Direction::AntiDiagonal
         } else {
-            (self.to.y..self.from.y + 1).rev().collect()
+            return None;
         };
+        let (offset, t1) = direction.line_through(x1, y1);
+        let (_, t2) = direction.line_through(x2, y2);
+        Some((Line { direction, offset }, t1.min(t2), t1.max(t2)))
+    }
+}
 
-        if self.from.x == self.to.x {
-            ys.iter()
-                .map(|y| Point {
-                    x: self.from.x,
-                    y: *y,
-                })
-                .collect()
-        } else if self.from.y == self.to.y {
-            xs.iter()
-                .map(|x| Point {
-                    x: *x,
-                    y: self.from.y,
-                })
-                .collect()
-        } else if (self.from.x - self.to.x).abs() == (self.from.y - self.to.y).abs() {
-            xs.iter()
-                .zip(ys.iter())
-                .map(|(x, y)| Point { x: *x, y: *y })
-                .collect()
-        } else {
-            vec![]
+fn count_overlaps<'a, I: IntoIterator<Item = &'a Segment>>(segments: I) -> usize {
+    let mut intervals: HashMap<Line, Vec<Interval>> = HashMap::new();
+    for s in segments {
+        if let Some((line, start, end)) = s.line() {
+            intervals.entry(line).or_default().push((start, end));
+        }
+    }
+
+    let mut covered = vec![];
+    let mut multi: HashMap<Line, Vec<Interval>> = HashMap::new();
+    for (line, is) in intervals {
+        let (union, overlaps) = sweep(is);
+        for i in union {
+            covered.push((line, i));
+        }
+        multi.insert(line, overlaps);
+    }
+
+    let mut total: i64 = multi
+        .values()
+        .flatten()
+        .map(|(start, end)| end - start + 1)
+        .sum();
+
+    let mut crossings = HashSet::new();
+    for (i, (l1, (s1, e1))) in covered.iter().enumerate() {
+        for (l2, (s2, e2)) in &covered[i + 1..] {
+            if let Some((x, y)) = l1.intersection(l2) {
+                let (_, t1) = l1.direction.line_through(x, y);
+                let (_, t2) = l2.direction.line_through(x, y);
+                if (s1..=e1).contains(&&t1) && (s2..=e2).contains(&&t2) {
+                    crossings.insert((x, y));
+                }
+            }
         }
     }
+
+    for (x, y) in crossings {
+        let n_multi = [
+            Direction::Horizontal,
+            Direction::Vertical,
+            Direction::Diagonal,
+            Direction::AntiDiagonal,
+        ]
+        .iter()
+        .filter(|&&direction| {
+            let (offset, t) = direction.line_through(x, y);
+            multi
+                .get(&Line { direction, offset })
+                .map(|is| is.iter().any(|&(start, end)| start <= t && t <= end))
+                .unwrap_or(false)
+        })
+        .count() as i64;
+        total += if n_multi == 0 { 1 } else { 1 - n_multi };
+    }
+
+    total as usize
 }
 
-fn solve<'a, I: IntoIterator<Item = &'a Segment>>(segments: I) -> usize {
-    let mut counts = HashMap::new();
-    for s in segments {
-        for p in s.points() {
-            *counts.entry(p).or_insert(0) += 1;
+fn sweep(intervals: Vec<Interval>) -> (Vec<Interval>, Vec<Interval>) {
+    let mut events: Vec<(i64, i32)> = intervals
+        .iter()
+        .flat_map(|&(start, end)| [(start, 1), (end + 1, -1)])
+        .collect();
+    events.sort_unstable();
+
+    let mut union = vec![];
+    let mut overlaps = vec![];
+    let mut depth = 0;
+    let mut union_start = 0;
+    let mut overlap_start = 0;
+    for (t, delta) in events {
+        let previous = depth;
+        depth += delta;
+        if previous == 0 && depth > 0 {
+            union_start = t;
+        } else if previous > 0 && depth == 0 {
+            union.push((union_start, t - 1));
+        }
+        if previous < 2 && depth >= 2 {
+            overlap_start = t;
+        } else if previous >= 2 && depth < 2 {
+            overlaps.push((overlap_start, t - 1));
         }
     }
+    (union, overlaps)
+}
 
-    counts.iter().filter(|(_, &v)| v >= 2).count()
+fn solve<'a, I: IntoIterator<Item = &'a Segment>>(
+    segments: I,
+    rasterization: Rasterization,
+) -> usize {
+    DensityMap::build(segments, rasterization).count_at_least(2)
+}
+
+#[derive(Debug)]
+struct DensityMap {
+    segments: HashMap<Point, Vec<usize>>,
+}
+
+impl DensityMap {
+    fn build<'a, I: IntoIterator<Item = &'a Segment>>(
+        segments: I,
+        rasterization: Rasterization,
+    ) -> DensityMap {
+        let mut map: HashMap<Point, Vec<usize>> = HashMap::new();
+        for (i, s) in segments.into_iter().enumerate() {
+            for p in s.points(rasterization) {
+                map.entry(p).or_default().push(i);
+            }
+        }
+        DensityMap { segments: map }
+    }
+
+    fn counts(&self) -> HashMap<Point, usize> {
+        self.segments.iter().map(|(p, s)| (*p, s.len())).collect()
+    }
+
+    fn density(&self, point: &Point) -> usize {
+        self.segments_through(point).len()
+    }
+
+    fn count_at_least(&self, k: usize) -> usize {
+        self.segments.values().filter(|s| s.len() >= k).count()
+    }
+
+    fn densest(&self) -> (usize, Vec<Point>) {
+        let max = self.segments.values().map(|s| s.len()).max().unwrap_or(0);
+        let mut points: Vec<Point> = self
+            .segments
+            .iter()
+            .filter(|(_, s)| s.len() == max)
+            .map(|(p, _)| *p)
+            .collect();
+        points.sort_unstable();
+        (max, points)
+    }
+
+    fn segments_through(&self, point: &Point) -> &[usize] {
+        self.segments.get(point).map(|s| &s[..]).unwrap_or(&[])
+    }
+
+    fn intersections(&self) -> Vec<(usize, usize, Vec<Point>)> {
+        let mut pairs: HashMap<(usize, usize), Vec<Point>> = HashMap::new();
+        for (p, segments) in &self.segments {
+            for (i, s1) in segments.iter().enumerate() {
+                for s2 in &segments[i + 1..] {
+                    pairs.entry((*s1, *s2)).or_default().push(*p);
+                }
+            }
+        }
+        let mut intersections: Vec<(usize, usize, Vec<Point>)> = pairs
+            .into_iter()
+            .map(|((s1, s2), mut points)| {
+                points.sort_unstable();
+                (s1, s2, points)
+            })
+            .collect();
+        intersections.sort_unstable();
+        intersections
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum SegmentFilter {
+    All,
+    AxisAligned,
+    Diagonal,
+}
+
+impl SegmentFilter {
+    fn matches(&self, segment: &Segment) -> bool {
+        let axis_aligned = segment.from.x == segment.to.x || segment.from.y == segment.to.y;
+        match self {
+            SegmentFilter::All => true,
+            SegmentFilter::AxisAligned => axis_aligned,
+            SegmentFilter::Diagonal => !axis_aligned,
+        }
+    }
+}
+
+impl FromStr for SegmentFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "all" => Ok(SegmentFilter::All),
+            "axis" => Ok(SegmentFilter::AxisAligned),
+            "diagonal" => Ok(SegmentFilter::Diagonal),
+            _ => Err(anyhow!("invalid filter: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct Viewport {
+    min_x: i32,
+    min_y: i32,
+    max_x: i32,
+    max_y: i32,
+}
+
+impl Viewport {
+    fn fit(counts: &HashMap<Point, usize>) -> Option<Viewport> {
+        Some(Viewport {
+            min_x: counts.keys().map(|p| p.x).min()?,
+            min_y: counts.keys().map(|p| p.y).min()?,
+            max_x: counts.keys().map(|p| p.x).max()?,
+            max_y: counts.keys().map(|p| p.y).max()?,
+        })
+    }
+
+    fn cells(&self, scale: usize) -> (usize, usize) {
+        let scale = scale.max(1);
+        let width = (self.max_x - self.min_x + 1).max(0) as usize;
+        let height = (self.max_y - self.min_y + 1).max(0) as usize;
+        (width.div_ceil(scale), height.div_ceil(scale))
+    }
+
+    fn density(&self, counts: &HashMap<Point, usize>, scale: usize, cx: usize, cy: usize) -> usize {
+        let scale = scale.max(1) as i32;
+        let x0 = self.min_x + cx as i32 * scale;
+        let y0 = self.min_y + cy as i32 * scale;
+        (y0..(y0 + scale).min(self.max_y + 1))
+            .flat_map(|y| (x0..(x0 + scale).min(self.max_x + 1)).map(move |x| Point { x, y }))
+            .filter_map(|p| counts.get(&p))
+            .copied()
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl FromStr for Viewport {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let coords = s
+            .split(',')
+            .map(|c| Ok(c.parse()?))
+            .collect::<Result<Vec<i32>>>()?;
+        match coords[..] {
+            [min_x, min_y, max_x, max_y] => Ok(Viewport {
+                min_x,
+                min_y,
+                max_x,
+                max_y,
+            }),
+            _ => Err(anyhow!("invalid viewport: {}", s)),
+        }
+    }
+}
+
+fn render_ascii(counts: &HashMap<Point, usize>, viewport: &Viewport, scale: usize) -> String {
+    let (width, height) = viewport.cells(scale);
+    let mut ascii = String::new();
+    for cy in 0..height {
+        for cx in 0..width {
+            ascii.push(match viewport.density(counts, scale, cx, cy) {
+                0 => '.',
+                n @ 1..=9 => char::from_digit(n as u32, 10).unwrap_or('#'),
+                _ => '#',
+            });
+        }
+        ascii.push('\n');
+    }
+    ascii
+}
+
+fn render_ppm(counts: &HashMap<Point, usize>, viewport: &Viewport, scale: usize) -> Vec<u8> {
+    let (width, height) = viewport.cells(scale);
+    let max = counts.values().copied().max().unwrap_or(0).max(1);
+    let mut ppm = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    for cy in 0..height {
+        for cx in 0..width {
+            let heat = viewport.density(counts, scale, cx, cy) as f64 / max as f64;
+            let channel = |offset: f64| ((heat * 3.0 - offset).clamp(0.0, 1.0) * 255.0) as u8;
+            ppm.extend([channel(0.0), channel(1.0), channel(2.0)]);
+        }
+    }
+    ppm
 }
 
 fn parse_segments<'a, I: IntoIterator<Item = &'a String>>(input: I) -> Result<Vec<Segment>> {
@@ -103,7 +538,10 @@ fn read_input<P: AsRef<std::path::Path>>(path: P) -> Result<Vec<String>> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{solve, Point, Segment};
+    use crate::{
+        count_overlaps, parse_segment, render_ascii, render_ppm, solve, DensityMap, Point,
+        Rasterization, Segment, SegmentFilter, Viewport,
+    };
 
     #[test]
     fn test_example() {
@@ -149,6 +587,189 @@ mod tests {
                 to: Point { x: 8, y: 2 },
             },
         ];
-        assert_eq!(solve(&segments), 12);
+        assert_eq!(solve(&segments, Rasterization::Strict), 12);
+        assert_eq!(solve(&segments, Rasterization::Bresenham), 12);
+        assert_eq!(count_overlaps(&segments), 12);
+    }
+
+    #[test]
+    fn test_count_overlaps() {
+        for t in 0..50 {
+            let segments: Vec<Segment> = (0..40)
+                .map(|k| {
+                    let v = t * 40 + k;
+                    let (x, y) = ((v * 7 + t) % 30, (v * v * 3 + k) % 30);
+                    let len = v * 11 % 15 - 7;
+                    let (dx, dy) = match (v * v + t) % 5 {
+                        0 => (len, 0),
+                        1 => (0, len),
+                        2 => (len, len),
+                        3 => (len, -len),
+                        _ => (len, v % 5),
+                    };
+                    Segment {
+                        from: Point { x, y },
+                        to: Point {
+                            x: x + dx,
+                            y: y + dy,
+                        },
+                    }
+                })
+                .collect();
+            assert_eq!(
+                count_overlaps(&segments),
+                solve(&segments, Rasterization::Strict)
+            );
+        }
+
+        let long = [
+            "0,0 -> 3000000,0",
+            "0,0 -> 0,3000000",
+            "0,0 -> 3000000,3000000",
+            "1000000,-10 -> 1000000,2000000",
+            "2000000,0 -> 0,2000000",
+        ]
+        .iter()
+        .map(|s| parse_segment(s).unwrap())
+        .collect::<Vec<Segment>>();
+        assert_eq!(count_overlaps(&long), 5);
+    }
+
+    #[test]
+    fn test_bresenham() {
+        let segment = parse_segment("0,0 -> 4,2").unwrap();
+        assert_eq!(segment.points(Rasterization::Strict), []);
+        assert_eq!(
+            segment.points(Rasterization::Bresenham),
+            [
+                Point { x: 0, y: 0 },
+                Point { x: 1, y: 1 },
+                Point { x: 2, y: 1 },
+                Point { x: 3, y: 2 },
+                Point { x: 4, y: 2 },
+            ]
+        );
+
+        for s in ["3,-2 -> -4,9", "-5,7 -> 6,5", "2,2 -> 2,2", "9,1 -> 1,9"] {
+            let segment = parse_segment(s).unwrap();
+            let points = segment.points(Rasterization::Bresenham);
+            let (dx, dy) = (
+                (segment.to.x - segment.from.x).abs(),
+                (segment.to.y - segment.from.y).abs(),
+            );
+            assert_eq!(points.len() as i32, dx.max(dy) + 1);
+            assert_eq!(points.first(), Some(&segment.from));
+            assert_eq!(points.last(), Some(&segment.to));
+            assert!(points
+                .windows(2)
+                .all(|w| (w[0].x - w[1].x).abs() <= 1 && (w[0].y - w[1].y).abs() <= 1));
+        }
+
+        let segments = ["0,0 -> 4,2", "0,2 -> 4,0", "0,1 -> 4,1"]
+            .iter()
+            .map(|s| parse_segment(s).unwrap())
+            .collect::<Vec<Segment>>();
+        assert_eq!(solve(&segments, Rasterization::Strict), 0);
+        assert_eq!(solve(&segments, Rasterization::Bresenham), 2);
+    }
+
+    #[test]
+    fn test_render() {
+        let segments = [
+            "0,9 -> 5,9",
+            "8,0 -> 0,8",
+            "9,4 -> 3,4",
+            "2,2 -> 2,1",
+            "7,0 -> 7,4",
+            "6,4 -> 2,0",
+            "0,9 -> 2,9",
+            "3,4 -> 1,4",
+            "0,0 -> 8,8",
+            "5,5 -> 8,2",
+        ]
+        .iter()
+        .map(|s| parse_segment(s).unwrap())
+        .collect::<Vec<Segment>>();
+
+        let axis: Vec<&Segment> = segments
+            .iter()
+            .filter(|s| SegmentFilter::AxisAligned.matches(s))
+            .collect();
+        let density = DensityMap::build(axis, Rasterization::Strict).counts();
+        let viewport: Viewport = "0,0,9,9".parse().unwrap();
+        assert_eq!(
+            render_ascii(&density, &viewport, 1),
+            concat!(
+                ".......1..\n",
+                "..1....1..\n",
+                "..1....1..\n",
+                ".......1..\n",
+                ".112111211\n",
+                "..........\n",
+                "..........\n",
+                "..........\n",
+                "..........\n",
+                "222111....\n",
+            )
+        );
+
+        let density = DensityMap::build(&segments, Rasterization::Strict).counts();
+        assert_eq!(
+            render_ascii(&density, &viewport, 1),
+            concat!(
+                "1.1....11.\n",
+                ".111...2..\n",
+                "..2.1.111.\n",
+                "...1.2.2..\n",
+                ".112313211\n",
+                "...1.2....\n",
+                "..1...1...\n",
+                ".1.....1..\n",
+                "1.......1.\n",
+                "222111....\n",
+            )
+        );
+        assert_eq!(render_ascii(&density, &viewport, 5), "33\n22\n");
+        assert_eq!(Viewport::fit(&density), Some(viewport));
+
+        let ppm = render_ppm(&density, &viewport, 2);
+        assert!(ppm.starts_with(b"P6\n5 5\n255\n"));
+        assert_eq!(ppm.len(), b"P6\n5 5\n255\n".len() + 5 * 5 * 3);
+        assert!("1,2,3".parse::<Viewport>().is_err());
+    }
+
+    #[test]
+    fn test_density_map() {
+        let segments = [
+            "0,0 -> 4,0",
+            "2,0 -> 2,4",
+            "0,2 -> 4,2",
+            "0,0 -> 4,4",
+            "4,0 -> 0,4",
+        ]
+        .iter()
+        .map(|s| parse_segment(s).unwrap())
+        .collect::<Vec<Segment>>();
+        let map = DensityMap::build(&segments, Rasterization::Strict);
+
+        assert_eq!(map.count_at_least(1), 19);
+        assert_eq!(map.count_at_least(2), 4);
+        assert_eq!(map.count_at_least(4), 1);
+        assert_eq!(map.count_at_least(5), 0);
+        assert_eq!(
+            map.count_at_least(2),
+            solve(&segments, Rasterization::Strict)
+        );
+
+        assert_eq!(map.densest(), (4, vec![Point { x: 2, y: 2 }]));
+        assert_eq!(map.density(&Point { x: 0, y: 0 }), 2);
+        assert_eq!(map.segments_through(&Point { x: 2, y: 2 }), [1, 2, 3, 4]);
+        assert_eq!(map.segments_through(&Point { x: 9, y: 9 }), []);
+
+        let intersections = map.intersections();
+        assert_eq!(intersections.len(), 9);
+        assert_eq!(intersections[0], (0, 1, vec![Point { x: 2, y: 0 }]));
+        assert_eq!(intersections[1], (0, 3, vec![Point { x: 0, y: 0 }]));
+        assert_eq!(intersections[8], (3, 4, vec![Point { x: 2, y: 2 }]));
     }
 }
@@ -1,47 +1,413 @@
-use anyhow::Result;
-use std::collections::HashMap;
+use anyhow::{anyhow, Result};
 use std::fs;
+use std::str::FromStr;
 
 fn main() -> Result<()> {
     let input = fs::read_to_string("input.txt")?;
     let nums = parse_nums(input.trim())?;
-    dbg!(evolve(80, &nums).len());
-    let mut counts = count(&nums);
-    evolve_counts(256, &mut counts);
-    let sum = counts.iter().fold(0, |s, (_, c)| s + c);
+    let args: Vec<String> = std::env::args().collect();
+    let default = LifeCycle::default();
+    let life_cycle = LifeCycle {
+        cycle: arg(&args, "--cycle")?.unwrap_or(default.cycle),
+        maturation: arg(&args, "--maturation")?.unwrap_or(default.maturation),
+        offspring: arg(&args, "--offspring")?.unwrap_or(default.offspring),
+        mortality: arg(&args, "--mortality")?,
+    };
+
+    dbg!(evolve(80, &Fish::spawn(&nums), &life_cycle).len());
+    let mut counts = count(&nums, &life_cycle)?;
+    evolve_counts(256, &mut counts, &life_cycle).ok_or(anyhow!("population overflow"))?;
+    let sum: u128 = counts.iter().sum();
     dbg!(sum);
+
+    if let Some(days) = arg::<u64>(&args, "--days")? {
+        let arithmetic = match arg(&args, "--modulus")? {
+            Some(0) => return Err(anyhow!("modulus must be positive")),
+            Some(m) => Arithmetic::Modular(m),
+            None => Arithmetic::Exact,
+        };
+        let total = population_after(days, &count(&nums, &life_cycle)?, &life_cycle, arithmetic)
+            .ok_or(anyhow!("population overflow, try --modulus"))?;
+        println!("population after {} days: {}", days, total);
+    }
+
+    if let Some(days) = arg::<usize>(&args, "--series")? {
+        let series = time_series(&count(&nums, &life_cycle)?, days, &life_cycle)
+            .ok_or(anyhow!("population overflow"))?;
+        match arg::<String>(&args, "--format")?.as_deref() {
+            Some("json") => println!("{}", series_json(&series)),
+            Some("csv") | None => print!("{}", series_csv(&series)),
+            Some(f) => return Err(anyhow!("unknown format: {}", f)),
+        }
+        if let Some(stats) = GrowthStats::of(&series) {
+            println!("mean growth rate: {:.4}", stats.mean_rate);
+            println!("min growth rate: {:.4}", stats.min_rate);
+            println!("max growth rate: {:.4}", stats.max_rate);
+            match stats.doubling_time {
+                Some(days) => println!("doubling time: {:.2} days", days),
+                None => println!("doubling time: none"),
+            }
+        }
+    }
     Ok(())
 }
 
-fn evolve_counts(times: usize, counts: &mut HashMap<&i32, usize>) {
+fn arg<T>(args: &[String], name: &str) -> Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    match args.iter().position(|a| a == name) {
+        Some(i) => Ok(Some(
+            args.get(i + 1)
+                .ok_or(anyhow!("missing value for {}", name))?
+                .parse()?,
+        )),
+        None => Ok(None),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct LifeCycle {
+    cycle: usize,
+    maturation: usize,
+    offspring: usize,
+    mortality: Option<usize>,
+}
+
+impl Default for LifeCycle {
+    fn default() -> Self {
+        LifeCycle {
+            cycle: 7,
+            maturation: 2,
+            offspring: 1,
+            mortality: None,
+        }
+    }
+}
+
+impl LifeCycle {
+    fn validate(&self) -> Result<()> {
+        if self.cycle == 0 {
+            return Err(anyhow!("cycle length must be positive"));
+        }
+        if self.mortality == Some(0) {
+            return Err(anyhow!("mortality age must be positive"));
+        }
+        Ok(())
+    }
+
+    fn reset_timer(&self) -> usize {
+        self.cycle - 1
+    }
+
+    fn newborn_timer(&self) -> usize {
+        self.cycle - 1 + self.maturation
+    }
+
+    fn timers(&self) -> usize {
+        self.newborn_timer() + 1
+    }
+
+    fn states(&self) -> usize {
+        self.timers() * self.mortality.unwrap_or(1)
+    }
+
+    fn state(&self, fish: &Fish) -> usize {
+        let age = if self.mortality.is_some() {
+            fish.age
+        } else {
+            0
+        };
+        age * self.timers() + fish.timer as usize
+    }
+
+    fn fish(&self, state: usize) -> Fish {
+        Fish {
+            timer: (state % self.timers()) as i32,
+            age: state / self.timers(),
+        }
+    }
+
+    fn next(&self, fish: &Fish) -> Vec<Fish> {
+        let age = fish.age + 1;
+        let alive = self.mortality.map(|m| age < m).unwrap_or(true);
+        let mut next = vec![];
+        if fish.timer == 0 {
+            if alive {
+                next.push(Fish {
+                    timer: self.reset_timer() as i32,
+                    age,
+                });
+            }
+            for _ in 0..self.offspring {
+                next.push(Fish {
+                    timer: self.newborn_timer() as i32,
+                    age: 0,
+                });
+            }
+        } else if alive {
+            next.push(Fish {
+                timer: fish.timer - 1,
+                age,
+            });
+        }
+        next
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Fish {
+    timer: i32,
+    age: usize,
+}
+
+impl Fish {
+    fn spawn(timers: &[i32]) -> Vec<Fish> {
+        timers.iter().map(|&timer| Fish { timer, age: 0 }).collect()
+    }
+}
+
+type Counts = Vec<u128>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Arithmetic {
+    Exact,
+    Modular(u64),
+}
+
+impl Arithmetic {
+    fn reduce(&self, n: u128) -> Option<u128> {
+        match self {
+            Arithmetic::Exact => Some(n),
+            Arithmetic::Modular(m) => n.checked_rem(*m as u128),
+        }
+    }
+
+    fn add(&self, a: u128, b: u128) -> Option<u128> {
+        self.reduce(a.checked_add(b)?)
+    }
+
+    fn mul(&self, a: u128, b: u128) -> Option<u128> {
+        self.reduce(a.checked_mul(b)?)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Matrix(Vec<Vec<u128>>);
+
+impl Matrix {
+    fn identity(size: usize) -> Matrix {
+        let mut m = vec![vec![0; size]; size];
+        for (i, row) in m.iter_mut().enumerate() {
+            row[i] = 1;
+        }
+        Matrix(m)
+    }
+
+    fn transition(life_cycle: &LifeCycle) -> Matrix {
+        let size = life_cycle.states();
+        let mut m = vec![vec![0; size]; size];
+        let edges: Vec<(usize, usize)> = (0..size)
+            .flat_map(|from| {
+                life_cycle
+                    .next(&life_cycle.fish(from))
+                    .into_iter()
+                    .map(move |to| (life_cycle.state(&to), from))
+            })
+            .collect();
+        for (to, from) in edges {
+            m[to][from] += 1;
+        }
+        Matrix(m)
+    }
+
+    fn size(&self) -> usize {
+        self.0.len()
+    }
+
+    fn mul(&self, other: &Matrix, arithmetic: Arithmetic) -> Option<Matrix> {
+        let size = self.size();
+        let mut m = vec![vec![0; size]; size];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                for k in 0..size {
+                    let product = arithmetic.mul(self.0[i][k], other.0[k][j])?;
+                    *cell = arithmetic.add(*cell, product)?;
+                }
+            }
+        }
+        Some(Matrix(m))
+    }
+
+    fn pow(&self, mut exp: u64, arithmetic: Arithmetic) -> Option<Matrix> {
+        let mut result = Matrix::identity(self.size());
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mul(&base, arithmetic)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.mul(&base, arithmetic)?;
+            }
+        }
+        Some(result)
+    }
+
+    fn apply(&self, counts: &[u128], arithmetic: Arithmetic) -> Option<Counts> {
+        let mut result = vec![0; self.size()];
+        for (i, r) in result.iter_mut().enumerate() {
+            for (j, c) in counts.iter().enumerate() {
+                *r = arithmetic.add(*r, arithmetic.mul(self.0[i][j], *c)?)?;
+            }
+        }
+        Some(result)
+    }
+}
+
+fn population_after(
+    days: u64,
+    counts: &[u128],
+    life_cycle: &LifeCycle,
+    arithmetic: Arithmetic,
+) -> Option<u128> {
+    let counts = Matrix::transition(life_cycle)
+        .pow(days, arithmetic)?
+        .apply(counts, arithmetic)?;
+    counts.iter().try_fold(0, |s, &c| arithmetic.add(s, c))
+}
+
+fn evolve_counts(times: usize, counts: &mut Counts, life_cycle: &LifeCycle) -> Option<()> {
     for _ in 0..times {
-        evolve_counts_step(counts);
+        evolve_counts_step(counts, life_cycle)?;
+    }
+    Some(())
+}
+
+fn evolve_counts_step(counts: &mut Counts, life_cycle: &LifeCycle) -> Option<()> {
+    let mut next = vec![0u128; counts.len()];
+    for (state, &n) in counts.iter().enumerate() {
+        if n == 0 {
+            continue;
+        }
+        for fish in life_cycle.next(&life_cycle.fish(state)) {
+            let to = life_cycle.state(&fish);
+            next[to] = next[to].checked_add(n)?;
+        }
+    }
+    *counts = next;
+    Some(())
+}
+
+fn histogram(counts: &[u128], life_cycle: &LifeCycle) -> Vec<u128> {
+    let mut histogram = vec![0; life_cycle.timers()];
+    for (state, &n) in counts.iter().enumerate() {
+        histogram[life_cycle.fish(state).timer as usize] += n;
+    }
+    histogram
+}
+
+fn time_series(counts: &[u128], days: usize, life_cycle: &LifeCycle) -> Option<Vec<Vec<u128>>> {
+    let mut counts = counts.to_vec();
+    let mut series = vec![histogram(&counts, life_cycle)];
+    for _ in 0..days {
+        evolve_counts_step(&mut counts, life_cycle)?;
+        series.push(histogram(&counts, life_cycle));
     }
+    Some(series)
 }
 
-fn evolve_counts_step(counts: &mut HashMap<&i32, usize>) {
-    let spawns = *counts.get(&0).unwrap_or(&0);
-    counts.insert(&0, *counts.get(&1).unwrap_or(&0));
-    counts.insert(&1, *counts.get(&2).unwrap_or(&0));
-    counts.insert(&2, *counts.get(&3).unwrap_or(&0));
-    counts.insert(&3, *counts.get(&4).unwrap_or(&0));
-    counts.insert(&4, *counts.get(&5).unwrap_or(&0));
-    counts.insert(&5, *counts.get(&6).unwrap_or(&0));
-    counts.insert(&6, *counts.get(&7).unwrap_or(&0) + spawns);
-    counts.insert(&7, *counts.get(&8).unwrap_or(&0));
-    counts.insert(&8, spawns);
+fn series_csv(series: &[Vec<u128>]) -> String {
+    let timers = series.first().map(|h| h.len()).unwrap_or(0);
+    let mut csv = String::from("day");
+    for t in 0..timers {
+        csv.push_str(&format!(",t{}", t));
+    }
+    csv.push_str(",total\n");
+    for (day, histogram) in series.iter().enumerate() {
+        csv.push_str(&day.to_string());
+        for n in histogram {
+            csv.push_str(&format!(",{}", n));
+        }
+        csv.push_str(&format!(",{}\n", histogram.iter().sum::<u128>()));
+    }
+    csv
+}
+
+fn series_json(series: &[Vec<u128>]) -> String {
+    let days = series
+        .iter()
+        .enumerate()
+        .map(|(day, histogram)| {
+            let timers = histogram
+                .iter()
+                .map(|n| n.to_string())
+                .collect::<Vec<String>>()
+                .join(",");
+            format!(
+                "{{\"day\":{},\"timers\":[{}],\"total\":{}}}",
+                day,
+                timers,
+                histogram.iter().sum::<u128>()
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+    format!("[{}]", days)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct GrowthStats {
+    mean_rate: f64,
+    min_rate: f64,
+    max_rate: f64,
+    doubling_time: Option<f64>,
+}
+
+impl GrowthStats {
+    fn of(series: &[Vec<u128>]) -> Option<GrowthStats> {
+        let totals: Vec<f64> = series
+            .iter()
+            .map(|h| h.iter().sum::<u128>() as f64)
+            .collect();
+        let rates: Vec<f64> = totals
+            .windows(2)
+            .filter(|w| w[0] > 0.0)
+            .map(|w| w[1] / w[0])
+            .collect();
+        if rates.is_empty() {
+            return None;
+        }
+        let mean_rate = rates.iter().map(|r| r.ln()).sum::<f64>() / rates.len() as f64;
+        let mean_rate = mean_rate.exp();
+        Some(GrowthStats {
+            mean_rate,
+            min_rate: rates.iter().copied().fold(f64::INFINITY, f64::min),
+            max_rate: rates.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            doubling_time: if mean_rate > 1.0 {
+                Some(2f64.ln() / mean_rate.ln())
+            } else {
+                None
+            },
+        })
+    }
 }
 
-fn count(nums: &Vec<i32>) -> HashMap<&i32, usize> {
-    let mut map = HashMap::new();
-    for n in nums {
-        *map.entry(n).or_insert(0) += 1;
+fn count(nums: &[i32], life_cycle: &LifeCycle) -> Result<Counts> {
+    life_cycle.validate()?;
+    let mut counts = vec![0; life_cycle.states()];
+    for fish in Fish::spawn(nums) {
+        if fish.timer < 0 || fish.timer as usize >= life_cycle.timers() {
+            return Err(anyhow!("invalid timer: {}", fish.timer));
+        }
+        counts[life_cycle.state(&fish)] += 1;
     }
-    map
+    Ok(counts)
 }
 
 fn parse_nums(input: &str) -> Result<Vec<i32>> {
-    input.split(',').map(|n| parse_num(n)).collect()
+    input.split(',').map(parse_num).collect()
 }
 
 fn parse_num(input: &str) -> Result<i32> {
@@ -49,47 +415,193 @@ fn parse_num(input: &str) -> Result<i32> {
     Ok(num)
 }
 
-fn evolve(times: usize, nums: &Vec<i32>) -> Vec<i32> {
+fn evolve(times: usize, fish: &[Fish], life_cycle: &LifeCycle) -> Vec<Fish> {
     if times > 0 {
-        evolve(times - 1, &evolve_step(nums))
+        evolve(times - 1, &evolve_step(fish, life_cycle), life_cycle)
     } else {
-        nums.iter().copied().collect()
+        fish.to_vec()
     }
 }
 
-fn evolve_step(nums: &Vec<i32>) -> Vec<i32> {
-    nums.iter().flat_map(|n| evolve_num(n)).collect()
+fn evolve_step(fish: &[Fish], life_cycle: &LifeCycle) -> Vec<Fish> {
+    fish.iter()
+        .flat_map(|f| evolve_num(f, life_cycle))
+        .collect()
 }
 
-fn evolve_num(n: &i32) -> Vec<i32> {
-    if *n == 0 {
-        vec![6, 8]
-    } else {
-        vec![n - 1]
-    }
+fn evolve_num(fish: &Fish, life_cycle: &LifeCycle) -> Vec<Fish> {
+    life_cycle.next(fish)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{count, evolve, evolve_counts};
+    use crate::{
+        count, evolve, evolve_counts, population_after, series_csv, series_json, time_series,
+        Arithmetic, Fish, GrowthStats, LifeCycle,
+    };
+
+    fn timers(fish: Vec<Fish>) -> Vec<i32> {
+        let mut timers: Vec<i32> = fish.iter().map(|f| f.timer).collect();
+        timers.sort();
+        timers
+    }
 
     #[test]
     fn test_evolve() {
+        let input = Fish::spawn(&[3, 4, 3, 1, 2]);
+        let life_cycle = LifeCycle::default();
+        assert_eq!(timers(evolve(1, &input, &life_cycle)), [0, 1, 2, 2, 3]);
+        let mut expected = [
+            6, 0, 6, 4, 5, 6, 0, 1, 1, 2, 6, 0, 1, 1, 1, 2, 2, 3, 3, 4, 6, 7, 8, 8, 8, 8,
+        ];
+        expected.sort();
+        assert_eq!(timers(evolve(18, &input, &life_cycle)), expected);
+        assert_eq!(evolve(80, &input, &life_cycle).len(), 5934);
+    }
+
+    #[test]
+    fn test_evolve_counts() {
+        let input = vec![3, 4, 3, 1, 2];
+        let life_cycle = LifeCycle::default();
+        let mut counts = count(&input, &life_cycle).unwrap();
+        evolve_counts(256, &mut counts, &life_cycle).unwrap();
+        assert_eq!(counts.iter().sum::<u128>(), 26984457539);
+    }
+
+    #[test]
+    fn test_population_after() {
         let input = vec![3, 4, 3, 1, 2];
-        assert_eq!(evolve(1, &input).sort(), vec![2, 3, 2, 0, 1].sort());
+        let life_cycle = LifeCycle::default();
+        let counts = count(&input, &life_cycle).unwrap();
+        for days in 0..60 {
+            assert_eq!(
+                population_after(days, &counts, &life_cycle, Arithmetic::Exact),
+                Some(evolve(days as usize, &Fish::spawn(&input), &life_cycle).len() as u128)
+            );
+        }
         assert_eq!(
-            evolve(18, &input).sort(),
-            vec![6, 0, 6, 4, 5, 6, 0, 1, 1, 2, 6, 0, 1, 1, 1, 2, 2, 3, 3, 4, 6, 7, 8, 8, 8, 8]
-                .sort()
+            population_after(256, &counts, &life_cycle, Arithmetic::Exact),
+            Some(26984457539)
         );
-        assert_eq!(evolve(80, &input).len(), 5934);
+        assert_eq!(
+            population_after(
+                256,
+                &counts,
+                &life_cycle,
+                Arithmetic::Modular(1_000_000_007)
+            ),
+            Some(26984457539 % 1_000_000_007)
+        );
+        assert_eq!(
+            population_after(5_000, &counts, &life_cycle, Arithmetic::Exact),
+            None
+        );
+        assert!(population_after(
+            10_000_000,
+            &counts,
+            &life_cycle,
+            Arithmetic::Modular(u64::MAX)
+        )
+        .is_some());
+        assert_eq!(
+            population_after(256, &counts, &life_cycle, Arithmetic::Modular(0)),
+            None
+        );
+        assert!(count(&[9], &life_cycle).is_err());
     }
 
     #[test]
-    fn test_evolve_counts() {
+    fn test_life_cycles() {
         let input = vec![3, 4, 3, 1, 2];
-        let mut counts = count(&input);
-        evolve_counts(256, &mut counts);
-        assert_eq!(counts.iter().fold(0, |s, (_, n)| s + n), 26984457539);
+        let life_cycles = [
+            LifeCycle {
+                cycle: 5,
+                maturation: 3,
+                offspring: 2,
+                mortality: None,
+            },
+            LifeCycle {
+                mortality: Some(10),
+                ..LifeCycle::default()
+            },
+            LifeCycle {
+                cycle: 5,
+                maturation: 0,
+                offspring: 1,
+                mortality: Some(9),
+            },
+        ];
+        for life_cycle in life_cycles {
+            let fish = Fish::spawn(&input);
+            let initial = count(&input, &life_cycle).unwrap();
+            for days in [0, 1, 7, 9, 10, 11, 40] {
+                let naive = evolve(days, &fish, &life_cycle).len() as u128;
+                let mut counts = initial.clone();
+                evolve_counts(days, &mut counts, &life_cycle).unwrap();
+                assert_eq!(counts.iter().sum::<u128>(), naive);
+                assert_eq!(
+                    population_after(days as u64, &initial, &life_cycle, Arithmetic::Exact),
+                    Some(naive)
+                );
+            }
+        }
+
+        let mortal = LifeCycle {
+            mortality: Some(3),
+            ..LifeCycle::default()
+        };
+        assert_eq!(evolve(3, &Fish::spawn(&[5]), &mortal), []);
+        assert_eq!(
+            evolve(3, &Fish::spawn(&[2]), &mortal),
+            [Fish { timer: 8, age: 0 }]
+        );
+        assert!(count(
+            &input,
+            &LifeCycle {
+                cycle: 0,
+                ..LifeCycle::default()
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_time_series() {
+        let life_cycle = LifeCycle::default();
+        let counts = count(&[3, 4, 3, 1, 2], &life_cycle).unwrap();
+        let series = time_series(&counts, 18, &life_cycle).unwrap();
+        assert_eq!(series.len(), 19);
+        assert_eq!(series[0], [0, 1, 1, 2, 1, 0, 0, 0, 0]);
+        assert_eq!(series[1], [1, 1, 2, 1, 0, 0, 0, 0, 0]);
+        assert_eq!(series[18], [3, 5, 3, 2, 2, 1, 5, 1, 4]);
+
+        let csv = series_csv(&series[..2]);
+        assert_eq!(
+            csv,
+            concat!(
+                "day,t0,t1,t2,t3,t4,t5,t6,t7,t8,total\n",
+                "0,0,1,1,2,1,0,0,0,0,5\n",
+                "1,1,1,2,1,0,0,0,0,0,5\n",
+            )
+        );
+        assert_eq!(
+            series_json(&series[..1]),
+            r#"[{"day":0,"timers":[0,1,1,2,1,0,0,0,0],"total":5}]"#
+        );
+
+        let mortal = LifeCycle {
+            mortality: Some(4),
+            ..LifeCycle::default()
+        };
+        let series = time_series(&count(&[1, 2], &mortal).unwrap(), 4, &mortal).unwrap();
+        assert_eq!(series[3], [0, 0, 0, 0, 0, 1, 1, 1, 1]);
+        assert_eq!(series[4], [0, 0, 0, 0, 0, 0, 1, 1, 0]);
+
+        let stats = GrowthStats::of(&time_series(&counts, 256, &life_cycle).unwrap()).unwrap();
+        assert_eq!(stats.min_rate, 1.0);
+        assert!(stats.max_rate > stats.mean_rate);
+        assert!((stats.mean_rate.powf(256.0) * 5.0 - 26984457539.0).abs() < 1.0);
+        assert!(stats.doubling_time.unwrap() > 7.0);
+        assert_eq!(GrowthStats::of(&series[..1]), None);
     }
 }
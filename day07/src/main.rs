@@ -1,25 +1,212 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use std::fs;
+use std::ops::RangeInclusive;
 
 fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(i) = args.iter().position(|a| a == "--points") {
+        let path = args.get(i + 1).ok_or(anyhow!("missing points file"))?;
+        let crabs = parse_points(&fs::read_to_string(path)?)?;
+        dbg!(separable_optimum(&crabs, &const_diff));
+        dbg!(separable_optimum(&crabs, &lin_diff));
+        dbg!(euclidean_optimum(&crabs));
+        return Ok(());
+    }
+
     let input = fs::read_to_string("input.txt")?;
     let nums = parse_nums(input.trim())?;
-    dbg!(min_cost(&nums, &const_diff));
-    dbg!(min_cost(&nums, &lin_diff));
+    if let Some(i) = args.iter().position(|a| a == "--curve") {
+        let cost_fn = match args.get(i + 1).map(|a| a.as_str()) {
+            Some("const") => const_diff,
+            Some("lin") => lin_diff,
+            c => return Err(anyhow!("unknown cost function: {:?}", c)),
+        };
+        if let Some(i) = args.iter().position(|a| a == "--cheapest") {
+            let range = parse_range(args.get(i + 1).ok_or(anyhow!("missing range"))?)?;
+            dbg!(convex_optimum_in(&nums, range, &cost_fn));
+            return Ok(());
+        }
+        let range = match args.iter().position(|a| a == "--range") {
+            Some(i) => parse_range(args.get(i + 1).ok_or(anyhow!("missing range"))?)?,
+            None => {
+                let min = *nums.iter().min().ok_or(anyhow!("empty input"))?;
+                let max = *nums.iter().max().ok_or(anyhow!("empty input"))?;
+                min..=max
+            }
+        };
+        let curve = CostCurve::new(&nums, range, &cost_fn).ok_or(anyhow!("empty curve"))?;
+        if let Some(i) = args.iter().position(|a| a == "--budget") {
+            let budget = args.get(i + 1).ok_or(anyhow!("missing budget"))?.parse()?;
+            dbg!(curve.within(budget));
+        } else {
+            print!("{}", curve.to_csv());
+        }
+        return Ok(());
+    }
+    if args.iter().any(|a| a == "--brute-force") {
+        dbg!(min_cost(&nums, &const_diff));
+        dbg!(min_cost(&nums, &lin_diff));
+        return Ok(());
+    }
+    dbg!(median_optimum(&nums).map(|(_, cost)| cost));
+    dbg!(convex_optimum(&nums, &lin_diff).map(|(_, cost)| cost));
     Ok(())
 }
 
-fn min_cost<F: Fn(i32, i32) -> i32>(nums: &Vec<i32>, cost_fn: &F) -> Option<i32> {
+fn min_cost<F: Fn(i32, i32) -> i32>(nums: &[i32], cost_fn: &F) -> Option<i32> {
     let min = *nums.iter().min()?;
     let max = *nums.iter().max()?;
-    (min..max)
+    (min..=max)
         .map(|n| total_cost(nums, n, cost_fn))
         .collect::<Option<Vec<i32>>>()?
         .into_iter()
         .min()
 }
 
-fn total_cost<F: Fn(i32, i32) -> i32>(nums: &Vec<i32>, num: i32, cost_fn: F) -> Option<i32> {
+fn median_optimum(nums: &[i32]) -> Option<(i32, i32)> {
+    let mut sorted = nums.to_vec();
+    sorted.sort_unstable();
+    let median = *sorted.get(sorted.len() / 2)?;
+    Some((median, total_cost(nums, median, const_diff)?))
+}
+
+fn convex_optimum<F: Fn(i32, i32) -> i32>(nums: &[i32], cost_fn: &F) -> Option<(i32, i32)> {
+    let min = *nums.iter().min()?;
+    let max = *nums.iter().max()?;
+    convex_optimum_in(nums, min..=max, cost_fn)
+}
+
+fn convex_optimum_in<F: Fn(i32, i32) -> i32>(
+    nums: &[i32],
+    range: RangeInclusive<i32>,
+    cost_fn: &F,
+) -> Option<(i32, i32)> {
+    let (mut lo, mut hi) = (*range.start(), *range.end());
+    if lo > hi {
+        return None;
+    }
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if total_cost(nums, mid, cost_fn)? <= total_cost(nums, mid + 1, cost_fn)? {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    Some((lo, total_cost(nums, lo, cost_fn)?))
+}
+
+fn separable_optimum<F: Fn(i32, i32) -> i32>(
+    crabs: &[Vec<i32>],
+    cost_fn: &F,
+) -> Option<(Vec<i32>, i32)> {
+    let dims = crabs.first()?.len();
+    if crabs.iter().any(|c| c.len() != dims) {
+        return None;
+    }
+    let mut position = vec![];
+    let mut cost = 0;
+    for axis in 0..dims {
+        let nums: Vec<i32> = crabs.iter().map(|c| c[axis]).collect();
+        let (p, c) = convex_optimum(&nums, cost_fn)?;
+        position.push(p);
+        cost += c;
+    }
+    Some((position, cost))
+}
+
+fn euclidean_optimum(crabs: &[Vec<i32>]) -> Option<(Vec<f64>, f64)> {
+    let dims = crabs.first()?.len();
+    if crabs.iter().any(|c| c.len() != dims) {
+        return None;
+    }
+    let points: Vec<Vec<f64>> = crabs
+        .iter()
+        .map(|c| c.iter().map(|&x| x as f64).collect())
+        .collect();
+    let mut position: Vec<f64> = (0..dims)
+        .map(|axis| points.iter().map(|p| p[axis]).sum::<f64>() / points.len() as f64)
+        .collect();
+
+    for _ in 0..1000 {
+        let mut weights = 0.0;
+        let mut next = vec![0.0; dims];
+        for p in &points {
+            let d = distance(p, &position);
+            if d < 1e-9 {
+                continue;
+            }
+            weights += 1.0 / d;
+            for (n, x) in next.iter_mut().zip(p) {
+                *n += x / d;
+            }
+        }
+        if weights == 0.0 {
+            break;
+        }
+        next.iter_mut().for_each(|n| *n /= weights);
+        let step = distance(&next, &position);
+        position = next;
+        if step < 1e-9 {
+            break;
+        }
+    }
+
+    let cost = points.iter().map(|p| distance(p, &position)).sum();
+    Some((position, cost))
+}
+
+fn distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - y) * (x - y))
+        .sum::<f64>()
+        .sqrt()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct CostCurve {
+    points: Vec<(i32, i32)>,
+}
+
+impl CostCurve {
+    fn new<F: Fn(i32, i32) -> i32>(
+        nums: &[i32],
+        range: RangeInclusive<i32>,
+        cost_fn: &F,
+    ) -> Option<CostCurve> {
+        let points = range
+            .map(|n| Some((n, total_cost(nums, n, cost_fn)?)))
+            .collect::<Option<Vec<(i32, i32)>>>()?;
+        Some(CostCurve { points })
+    }
+
+    fn optimum(&self) -> Option<(i32, i32)> {
+        self.points.iter().copied().min_by_key(|&(_, cost)| cost)
+    }
+
+    fn within(&self, budget: i32) -> Vec<i32> {
+        let (_, best) = match self.optimum() {
+            Some(o) => o,
+            None => return vec![],
+        };
+        self.points
+            .iter()
+            .filter(|&&(_, cost)| cost <= best.saturating_add(budget))
+            .map(|&(n, _)| n)
+            .collect()
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("position,cost\n");
+        for (n, cost) in &self.points {
+            csv.push_str(&format!("{},{}\n", n, cost));
+        }
+        csv
+    }
+}
+
+fn total_cost<F: Fn(i32, i32) -> i32>(nums: &[i32], num: i32, cost_fn: F) -> Option<i32> {
     nums.iter().map(|n| cost_fn(num, *n)).reduce(|s, n| s + n)
 }
 
@@ -33,7 +220,22 @@ fn lin_diff(x: i32, y: i32) -> i32 {
 }
 
 fn parse_nums(input: &str) -> Result<Vec<i32>> {
-    input.split(',').map(|n| parse_num(n)).collect()
+    input.split(',').map(parse_num).collect()
+}
+
+fn parse_points(input: &str) -> Result<Vec<Vec<i32>>> {
+    input
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| parse_nums(l.trim()))
+        .collect()
+}
+
+fn parse_range(input: &str) -> Result<RangeInclusive<i32>> {
+    let (start, end) = input
+        .split_once(',')
+        .ok_or(anyhow!("invalid range: {}", input))?;
+    Ok(parse_num(start)?..=parse_num(end)?)
 }
 
 fn parse_num(input: &str) -> Result<i32> {
@@ -43,12 +245,110 @@ fn parse_num(input: &str) -> Result<i32> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{const_diff, lin_diff, min_cost};
+    use crate::{
+        const_diff, convex_optimum, convex_optimum_in, euclidean_optimum, lin_diff, median_optimum,
+        min_cost, parse_points, parse_range, separable_optimum, total_cost, CostCurve,
+    };
+    use std::ops::RangeInclusive;
 
     #[test]
     fn test_min_cost() {
         let nums = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
         assert_eq!(min_cost(&nums, &const_diff), Some(37));
         assert_eq!(min_cost(&nums, &lin_diff), Some(168));
+        assert_eq!(min_cost(&[0, 10, 10, 10], &const_diff), Some(10));
+    }
+
+    #[test]
+    fn test_optimum() {
+        let nums = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+        assert_eq!(median_optimum(&nums), Some((2, 37)));
+        assert_eq!(convex_optimum(&nums, &const_diff), Some((2, 37)));
+        assert_eq!(convex_optimum(&nums, &lin_diff), Some((5, 168)));
+        assert_eq!(convex_optimum_in(&nums, 8..=20, &lin_diff), Some((8, 223)));
+        assert_eq!(
+            convex_optimum_in(&nums, RangeInclusive::new(20, 8), &lin_diff),
+            None
+        );
+        assert_eq!(median_optimum(&[]), None);
+
+        let squared = |x: i32, y: i32| (x - y) * (x - y);
+        for t in 0..100 {
+            let nums: Vec<i32> = (0..t % 20 + 1)
+                .map(|k| (t * 37 + k * k * 11 + k * 7) % 200 - 50)
+                .collect();
+            assert_eq!(
+                median_optimum(&nums).map(|(_, c)| c),
+                min_cost(&nums, &const_diff)
+            );
+            for cost_fn in [&const_diff as &dyn Fn(i32, i32) -> i32, &lin_diff, &squared] {
+                let (position, cost) = convex_optimum(&nums, &cost_fn).unwrap();
+                assert_eq!(Some(cost), min_cost(&nums, &cost_fn));
+                assert_eq!(total_cost(&nums, position, cost_fn), Some(cost));
+            }
+        }
+    }
+
+    #[test]
+    fn test_multi_dimensional() {
+        let nums = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+        let crabs: Vec<Vec<i32>> = nums.iter().map(|&n| vec![n]).collect();
+        assert_eq!(separable_optimum(&crabs, &const_diff), Some((vec![2], 37)));
+        assert_eq!(separable_optimum(&crabs, &lin_diff), Some((vec![5], 168)));
+
+        let crabs: Vec<Vec<i32>> = nums.iter().map(|&n| vec![n, 2 * n, -n]).collect();
+        assert_eq!(
+            separable_optimum(&crabs, &const_diff),
+            Some((vec![2, 4, -2], 37 * 4))
+        );
+        assert_eq!(separable_optimum(&[vec![1, 2], vec![3]], &const_diff), None);
+
+        let crabs = parse_points("0,0\n4,0\n0,3\n").unwrap();
+        let (position, cost) = euclidean_optimum(&crabs).unwrap();
+        assert_eq!(position.len(), 2);
+        for candidate in [[0.0, 0.0], [1.0, 1.0], [4.0 / 3.0, 1.0], [0.5, 0.5]] {
+            let candidate_cost: f64 = crabs
+                .iter()
+                .map(|c| {
+                    ((c[0] as f64 - candidate[0]).powi(2) + (c[1] as f64 - candidate[1]).powi(2))
+                        .sqrt()
+                })
+                .sum();
+            assert!(cost <= candidate_cost + 1e-6);
+        }
+
+        let (position, cost) = euclidean_optimum(&[vec![1, 1], vec![5, 1], vec![3, 7]]).unwrap();
+        assert!((position[0] - 3.0).abs() < 1e-6);
+        assert!(cost > 0.0);
+        assert_eq!(
+            euclidean_optimum(&[vec![2, 2]]),
+            Some((vec![2.0, 2.0], 0.0))
+        );
+    }
+
+    #[test]
+    fn test_cost_curve() {
+        let nums = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+        let curve = CostCurve::new(&nums, 0..=16, &const_diff).unwrap();
+        assert_eq!(curve.points.len(), 17);
+        assert_eq!(curve.points[1], (1, 41));
+        assert_eq!(curve.points[3], (3, 39));
+        assert_eq!(curve.points[10], (10, 71));
+        assert_eq!(curve.optimum(), Some((2, 37)));
+        assert_eq!(curve.within(0), [2]);
+        assert_eq!(curve.within(4), [1, 2, 3, 4]);
+        assert_eq!(curve.within(i32::MAX).len(), curve.points.len());
+        assert_eq!(
+            convex_optimum_in(&nums, parse_range("5,12").unwrap(), &const_diff),
+            Some((5, 45))
+        );
+        assert_eq!(
+            convex_optimum_in(&nums, parse_range("20,30").unwrap(), &const_diff),
+            Some((20, 151))
+        );
+
+        let curve = CostCurve::new(&nums, 4..=6, &lin_diff).unwrap();
+        assert_eq!(curve.to_csv(), "position,cost\n4,170\n5,168\n6,176\n");
+        assert!(parse_range("1").is_err());
     }
 }
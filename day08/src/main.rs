@@ -1,26 +1,71 @@
 use anyhow::{anyhow, Result};
 use itertools::Itertools;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
 
 fn main() -> Result<()> {
-    let digits = parse_digits("abcefg cf acdeg acdfg bcdf abdfg abdefg acf abcdefg abcdfg")
-        .into_iter()
-        .collect();
+    let digits: Vec<String> =
+        parse_digits("abcefg cf acdeg acdfg bcdf abdfg abdefg acf abcdefg abcdfg");
     let input = read_input("input.txt")?;
     let entries = parse_entries(input).ok_or(anyhow!("empty input!"))?;
     let solution_one = solve_one(&entries).ok_or(anyhow!("empty input!"))?;
     dbg!(solution_one);
-    let solution_two = solve_two(digits, &entries).ok_or(anyhow!("empty input!"))?;
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == "--reference") {
+        let solution_two = solve_two(digits, &entries).ok_or(anyhow!("empty input!"))?;
+        dbg!(solution_two);
+        return Ok(());
+    }
+
+    let display = match args.iter().position(|a| a == "--display") {
+        Some(i) => {
+            let path = args.get(i + 1).ok_or(anyhow!("missing display file"))?;
+            parse_display(&read_input(path)?)?
+        }
+        None => Display::new(&digits).ok_or(anyhow!("invalid display"))?,
+    };
+    for (a, b) in display.ambiguities() {
+        eprintln!(
+            "warning: symbols {} and {} cannot be told apart",
+            display.labels[a], display.labels[b]
+        );
+    }
+    let diagnose = args.iter().any(|a| a == "--diagnose");
+    let mut solution_two = 0;
+    for (n, entry) in entries.iter().enumerate() {
+        let readings = display
+            .readings(entry)
+            .ok_or(anyhow!("entry {}: invalid pattern", n + 1))?;
+        match readings.as_slice() {
+            [symbols] => solution_two += display.value(symbols),
+            _ if diagnose => {
+                let diagnoses = display
+                    .diagnose(entry)
+                    .map_err(|e| anyhow!("entry {}: {}", n + 1, e))?;
+                for diagnosis in diagnoses {
+                    println!("entry {}: {}", n + 1, diagnosis.report(&display, entry));
+                }
+            }
+            [] => return Err(anyhow!("entry {}: no consistent wiring", n + 1)),
+            _ => {
+                let readings: Vec<String> = readings.iter().map(|r| display.render(r)).collect();
+                return Err(anyhow!(
+                    "entry {}: ambiguous output, could read {}",
+                    n + 1,
+                    readings.join(" or ")
+                ));
+            }
+        }
+    }
     dbg!(solution_two);
     Ok(())
 }
 
 type Entry = (Vec<String>, Vec<String>);
 
-fn solve_one(entries: &Vec<Entry>) -> Option<usize> {
+fn solve_one(entries: &[Entry]) -> Option<usize> {
     entries
         .iter()
         .map(|(_, o)| {
@@ -31,7 +76,7 @@ fn solve_one(entries: &Vec<Entry>) -> Option<usize> {
         .reduce(|s, c| s + c)
 }
 
-fn solve_two(digits: Vec<String>, entries: &Vec<Entry>) -> Option<usize> {
+fn solve_two(digits: Vec<String>, entries: &[Entry]) -> Option<usize> {
     let os = entries
         .iter()
         .map(|e| solve_two_entry(&digits, e))
@@ -39,48 +84,420 @@ fn solve_two(digits: Vec<String>, entries: &Vec<Entry>) -> Option<usize> {
     Some(os.iter().fold(0, |s, n| s + *n))
 }
 
-fn solve_two_entry(digits: &Vec<String>, entry: &Entry) -> Option<usize> {
+fn solve_two_entry(digits: &[String], entry: &Entry) -> Option<usize> {
     let mappings = ('a'..'h')
         .permutations(7)
         .map(|p| ('a'..'h').zip(p).collect::<HashMap<char, char>>())
         .collect::<Vec<HashMap<char, char>>>();
     let (patterns, output) = entry;
     let mapping = mappings.iter().find(|m| {
-        apply_mapping(patterns, m)
-            .iter()
-            .collect::<HashSet<&String>>()
-            == digits.iter().collect::<HashSet<&String>>()
+        apply_mapping(patterns, m).map(|ps| ps.iter().cloned().collect::<HashSet<String>>())
+            == Some(digits.iter().cloned().collect::<HashSet<String>>())
     })?;
-    apply_mapping(output, mapping)
+    apply_mapping(output, mapping)?
         .iter()
-        .map(|s| digits.iter().position(|d| d == s).unwrap())
+        .map(|s| digits.iter().position(|d| d == s))
+        .collect::<Option<Vec<usize>>>()?
+        .into_iter()
         .zip((0..output.len()).rev())
         .map(|(d, e)| d * 10_usize.pow(e.try_into().unwrap()))
         .reduce(|s, n| s + n)
 }
 
+type Mask = u32;
+
+fn to_mask(s: &str) -> Option<Mask> {
+    s.chars().try_fold(0, |m, c| {
+        let bit = (c as u32).checked_sub('a' as u32)?;
+        if bit >= Mask::BITS {
+            return None;
+        }
+        Some(m | 1 << bit)
+    })
+}
+
+fn signature(patterns: &[Mask], bit: usize) -> Vec<u32> {
+    let mut signature: Vec<u32> = patterns
+        .iter()
+        .filter(|&&p| p >> bit & 1 == 1)
+        .map(|p| p.count_ones())
+        .collect();
+    signature.sort_unstable();
+    signature
+}
+
+fn rewire(pattern: Mask, wiring: &[usize]) -> Mask {
+    wiring
+        .iter()
+        .enumerate()
+        .filter(|&(w, _)| pattern >> w & 1 == 1)
+        .fold(0, |m, (_, &s)| m | 1 << s)
+}
+
+fn segment_name(segment: usize) -> char {
+    (b'a' + segment as u8) as char
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fault {
+    Stuck(usize),
+    Dead(usize),
+}
+
+impl Fault {
+    fn apply(self, pattern: Mask) -> Mask {
+        match self {
+            Fault::Stuck(s) => pattern | 1 << s,
+            Fault::Dead(s) => pattern & !(1 << s),
+        }
+    }
+}
+
+impl std::fmt::Display for Fault {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Fault::Stuck(s) => write!(f, "segment {} stuck on", segment_name(*s)),
+            Fault::Dead(s) => write!(f, "segment {} dead", segment_name(*s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Diagnosis {
+    fault: Option<Fault>,
+    mismatched: usize,
+    wirings: Vec<Vec<usize>>,
+}
+
+impl Diagnosis {
+    fn explain(&self, display: &Display, pattern: Mask, wiring: &[usize]) -> Vec<usize> {
+        let rewired = rewire(pattern, wiring);
+        (0..display.digits.len())
+            .filter(|&d| {
+                self.fault
+                    .map_or(display.digits[d], |f| f.apply(display.digits[d]))
+                    == rewired
+            })
+            .collect()
+    }
+
+    fn report(&self, display: &Display, entry: &Entry) -> String {
+        let output: Vec<Mask> = entry.1.iter().filter_map(|o| to_mask(o)).collect();
+        let readings: BTreeSet<String> = self
+            .wirings
+            .iter()
+            .map(|wiring| {
+                output
+                    .iter()
+                    .map(|&o| match self.explain(display, o, wiring).as_slice() {
+                        [d] => display.labels[*d].clone(),
+                        _ => "?".to_string(),
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut report = match self.fault {
+            Some(fault) => fault.to_string(),
+            None => "no faulty segment".to_string(),
+        };
+        if self.mismatched > 0 {
+            report += &format!(", {} unexplained patterns", self.mismatched);
+        }
+        let readings: Vec<String> = readings.into_iter().collect();
+        format!(
+            "{}, {} wirings, reads {}",
+            report,
+            self.wirings.len(),
+            readings.join(" or ")
+        )
+    }
+}
+
+const MAX_DIAGNOSED_SEGMENTS: usize = 8;
+
+#[derive(Debug, Clone)]
+struct Display {
+    labels: Vec<String>,
+    digits: Vec<Mask>,
+    segments: usize,
+    signatures: Vec<Vec<u32>>,
+}
+
+impl Display {
+    fn new(digits: &[String]) -> Option<Display> {
+        let labels = (0..digits.len()).map(|d| d.to_string()).collect();
+        Display::with_labels(labels, digits)
+    }
+
+    fn with_labels(labels: Vec<String>, digits: &[String]) -> Option<Display> {
+        if labels.len() != digits.len() {
+            return None;
+        }
+        let digits = digits
+            .iter()
+            .map(|d| to_mask(d))
+            .collect::<Option<Vec<Mask>>>()?;
+        let all = digits.iter().fold(0, |a, d| a | d);
+        let segments = (Mask::BITS - all.leading_zeros()) as usize;
+        let signatures = (0..segments).map(|s| signature(&digits, s)).collect();
+        Some(Display {
+            labels,
+            digits,
+            segments,
+            signatures,
+        })
+    }
+
+    /// Pairs of symbols that some rewiring of the display swaps.
+    fn ambiguities(&self) -> Vec<(usize, usize)> {
+        let mut pairs = BTreeSet::new();
+        for wiring in self.wirings(&self.digits) {
+            for (i, &d) in self.digits.iter().enumerate() {
+                let rewired = rewire(d, &wiring);
+                for (j, _) in self
+                    .digits
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &e)| e == rewired)
+                {
+                    if i != j {
+                        pairs.insert((i.min(j), i.max(j)));
+                    }
+                }
+            }
+        }
+        pairs.into_iter().collect()
+    }
+
+    fn wirings(&self, patterns: &[Mask]) -> Vec<Vec<usize>> {
+        if patterns.iter().any(|p| p >> self.segments != 0) {
+            return vec![];
+        }
+        let candidates: Vec<Mask> = (0..self.segments)
+            .map(|w| {
+                let signature = signature(patterns, w);
+                (0..self.segments)
+                    .filter(|&s| self.signatures[s] == signature)
+                    .fold(0, |m, s| m | 1 << s)
+            })
+            .collect();
+        let mut expected = self.digits.clone();
+        expected.sort_unstable();
+
+        let mut wirings = vec![];
+        self.search(
+            &candidates,
+            0,
+            &mut vec![],
+            patterns,
+            &expected,
+            &mut wirings,
+        );
+        wirings
+    }
+
+    fn search(
+        &self,
+        candidates: &[Mask],
+        used: Mask,
+        wiring: &mut Vec<usize>,
+        patterns: &[Mask],
+        expected: &[Mask],
+        wirings: &mut Vec<Vec<usize>>,
+    ) {
+        let w = wiring.len();
+        if w == self.segments {
+            let mut rewired: Vec<Mask> = patterns.iter().map(|&p| rewire(p, wiring)).collect();
+            rewired.sort_unstable();
+            if rewired == expected {
+                wirings.push(wiring.clone());
+            }
+            return;
+        }
+        for s in 0..self.segments {
+            if (candidates[w] & !used) >> s & 1 == 1 {
+                wiring.push(s);
+                self.search(
+                    candidates,
+                    used | 1 << s,
+                    wiring,
+                    patterns,
+                    expected,
+                    wirings,
+                );
+                wiring.pop();
+            }
+        }
+    }
+
+    fn readings(&self, entry: &Entry) -> Option<Vec<Vec<usize>>> {
+        let (patterns, output) = entry;
+        let patterns = patterns
+            .iter()
+            .map(|p| to_mask(p))
+            .collect::<Option<Vec<Mask>>>()?;
+        let output = output
+            .iter()
+            .map(|o| to_mask(o))
+            .collect::<Option<Vec<Mask>>>()?;
+        let readings: BTreeSet<Vec<usize>> = self
+            .wirings(&patterns)
+            .iter()
+            .filter_map(|wiring| {
+                output
+                    .iter()
+                    .map(|&o| {
+                        let rewired = rewire(o, wiring);
+                        self.digits.iter().position(|&d| d == rewired)
+                    })
+                    .collect()
+            })
+            .collect();
+        Some(readings.into_iter().collect())
+    }
+
+    /// Searches every wiring, so only small displays can be diagnosed.
+    fn diagnose(&self, entry: &Entry) -> Result<Vec<Diagnosis>> {
+        if self.segments > MAX_DIAGNOSED_SEGMENTS {
+            return Err(anyhow!(
+                "cannot diagnose a {}-segment display, at most {} segments are supported",
+                self.segments,
+                MAX_DIAGNOSED_SEGMENTS
+            ));
+        }
+        let (patterns, output) = entry;
+        let observed = patterns
+            .iter()
+            .chain(output)
+            .map(|p| to_mask(p))
+            .collect::<Option<Vec<Mask>>>()
+            .filter(|ps| ps.iter().all(|p| p >> self.segments == 0))
+            .ok_or(anyhow!("invalid pattern"))?;
+        let faults: Vec<Fault> = (0..self.segments)
+            .flat_map(|s| [Fault::Stuck(s), Fault::Dead(s)])
+            .collect();
+        for mismatched in 0..=observed.len() {
+            let diagnose = |fault: Option<Fault>| {
+                let wirings = self.fit(&observed, fault, mismatched);
+                (!wirings.is_empty()).then_some(Diagnosis {
+                    fault,
+                    mismatched,
+                    wirings,
+                })
+            };
+            if let Some(diagnosis) = diagnose(None) {
+                return Ok(vec![diagnosis]);
+            }
+            let diagnoses: Vec<Diagnosis> =
+                faults.iter().filter_map(|&f| diagnose(Some(f))).collect();
+            if !diagnoses.is_empty() {
+                return Ok(diagnoses);
+            }
+        }
+        Ok(vec![])
+    }
+
+    fn fit(&self, observed: &[Mask], fault: Option<Fault>, mismatched: usize) -> Vec<Vec<usize>> {
+        let faulted: Vec<Mask> = self
+            .digits
+            .iter()
+            .map(|&d| fault.map_or(d, |f| f.apply(d)))
+            .collect();
+        let mut wirings = vec![];
+        self.fit_search(observed, &faulted, mismatched, 0, &mut vec![], &mut wirings);
+        wirings
+    }
+
+    fn fit_search(
+        &self,
+        observed: &[Mask],
+        faulted: &[Mask],
+        mismatched: usize,
+        used: Mask,
+        wiring: &mut Vec<usize>,
+        wirings: &mut Vec<Vec<usize>>,
+    ) {
+        // A pattern unexplained on the segments wired so far stays unexplained.
+        let unexplained = observed
+            .iter()
+            .filter(|&&p| {
+                let rewired = rewire(p, wiring);
+                !faulted.iter().any(|&d| d & used == rewired)
+            })
+            .count();
+        if unexplained > mismatched {
+            return;
+        }
+        if wiring.len() == self.segments {
+            wirings.push(wiring.clone());
+            return;
+        }
+        for s in 0..self.segments {
+            if used >> s & 1 == 0 {
+                wiring.push(s);
+                self.fit_search(
+                    observed,
+                    faulted,
+                    mismatched,
+                    used | 1 << s,
+                    wiring,
+                    wirings,
+                );
+                wiring.pop();
+            }
+        }
+    }
+
+    fn value(&self, symbols: &[usize]) -> usize {
+        symbols.iter().fold(0, |n, s| n * self.labels.len() + s)
+    }
+
+    fn render(&self, symbols: &[usize]) -> String {
+        symbols.iter().map(|&s| self.labels[s].as_str()).collect()
+    }
+}
+
+fn parse_display(lines: &[String]) -> Result<Display> {
+    let (labels, patterns): (Vec<String>, Vec<String>) = lines
+        .iter()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| {
+            let (label, pattern) = l
+                .split_once(char::is_whitespace)
+                .ok_or(anyhow!("expected label and pattern: {}", l))?;
+            Ok((label.to_string(), pattern.trim().to_string()))
+        })
+        .collect::<Result<Vec<(String, String)>>>()?
+        .into_iter()
+        .unzip();
+    Display::with_labels(labels, &patterns).ok_or(anyhow!("invalid display"))
+}
+
 fn apply_mapping<'a, I: IntoIterator<Item = &'a String>>(
     patterns: I,
     mapping: &HashMap<char, char>,
-) -> Vec<String> {
+) -> Option<Vec<String>> {
     patterns
         .into_iter()
         .map(|p| {
             let mut np = p
                 .chars()
-                .map(|d| *mapping.get(&d).unwrap())
-                .collect::<Vec<char>>();
+                .map(|d| mapping.get(&d).copied())
+                .collect::<Option<Vec<char>>>()?;
             np.sort();
-            np.iter().collect()
+            Some(np.iter().collect())
         })
         .collect()
 }
 
 fn parse_entries(ss: Vec<String>) -> Option<Vec<Entry>> {
-    ss.iter().map(parse_entry).collect::<Option<Vec<Entry>>>()
+    ss.iter()
+        .map(|s| parse_entry(s))
+        .collect::<Option<Vec<Entry>>>()
 }
 
-fn parse_entry(s: &String) -> Option<Entry> {
+fn parse_entry(s: &str) -> Option<Entry> {
     let (signal_patterns_str, output_str) = s.split_once('|')?;
     let signal_patterns = parse_digits(signal_patterns_str).into_iter().collect();
     let output = parse_digits(output_str);
@@ -88,7 +505,7 @@ fn parse_entry(s: &String) -> Option<Entry> {
 }
 
 fn parse_digits(s: &str) -> Vec<String> {
-    s.trim().split_whitespace().map(|s| s.to_string()).collect()
+    s.split_whitespace().map(|s| s.to_string()).collect()
 }
 
 fn read_input<P: AsRef<std::path::Path>>(path: P) -> Result<Vec<String>> {
@@ -98,13 +515,14 @@ fn read_input<P: AsRef<std::path::Path>>(path: P) -> Result<Vec<String>> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{parse_digits, parse_entries, parse_entry, solve_one, solve_two_entry};
+    use crate::{
+        parse_digits, parse_display, parse_entries, parse_entry, solve_one, solve_two_entry,
+        Diagnosis, Display, Entry, Fault,
+    };
 
     #[test]
     fn test_solution() {
-        let digits = parse_digits("abcefg cf acdeg acdfg bcdf abdfg abdefg acf abcdefg abcdfg")
-            .into_iter()
-            .collect();
+        let digits = parse_digits("abcefg cf acdeg acdfg bcdf abdfg abdefg acf abcdefg abcdfg");
         let input = vec![
             "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe".to_string(),
             "edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc".to_string(),
@@ -119,17 +537,197 @@ mod tests {
         ];
         let entries = parse_entries(input).unwrap();
         assert_eq!(solve_one(&entries).unwrap(), 26);
+
+        let display = Display::new(&digits).unwrap();
+        let decoded: Vec<usize> = entries
+            .iter()
+            .map(|e| decode(&display, e).unwrap())
+            .collect();
+        assert_eq!(
+            decoded,
+            [8394, 9781, 1197, 9361, 4873, 8418, 4548, 1625, 8717, 4315]
+        );
+        for e in &entries {
+            assert_eq!(decode(&display, e), solve_two_entry(&digits, e));
+        }
         assert_eq!(
             solve_two_entry(
                 &digits,
                 &parse_entry(
-                    &"acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab |
+                    "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab |
 cdfeb fcadb cdfeb cdbaf"
-                        .to_string()
                 )
                 .unwrap()
             ),
             Some(5353)
         );
+        assert_eq!(
+            decode(
+                &display,
+                &parse_entry(
+                    "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab |
+cdfeb fcadb cdfeb cdbaf"
+                )
+                .unwrap()
+            ),
+            Some(5353)
+        );
+        assert_eq!(decode(&display, &parse_entry("ab cd | ab").unwrap()), None);
+    }
+
+    fn decode(display: &Display, entry: &Entry) -> Option<usize> {
+        match display.readings(entry)?.as_slice() {
+            [symbols] => Some(display.value(symbols)),
+            _ => None,
+        }
+    }
+
+    fn scramble(display: &Display, wires: &str, output: &[usize]) -> Entry {
+        let wires: Vec<char> = wires.chars().collect();
+        let wire = |p: &u32| -> String {
+            (0..display.segments)
+                .filter(|s| p >> s & 1 == 1)
+                .map(|s| wires[s])
+                .collect()
+        };
+        let patterns = display.digits.iter().rev().map(wire).collect();
+        let output = output.iter().map(|&o| wire(&display.digits[o])).collect();
+        (patterns, output)
+    }
+
+    #[test]
+    fn test_display() {
+        let hex: Vec<String> = [
+            "0 abcefg",
+            "1 cf",
+            "2 acdeg",
+            "3 acdfg",
+            "4 bcdf",
+            "5 abdfg",
+            "6 abdefg",
+            "7 acf",
+            "8 abcdefg",
+            "9 abcdfg",
+            "# letters",
+            "A abcdef",
+            "b bdefg",
+            "C abeg",
+            "d cdefg",
+            "E abdeg",
+            "F abde",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+        let display = parse_display(&hex).unwrap();
+        assert_eq!(display.labels.len(), 16);
+        assert!(display.ambiguities().is_empty());
+
+        let entry = scramble(&display, "gfedcba", &[12, 0, 15, 15, 14, 14]);
+        assert_eq!(
+            display.render(&display.readings(&entry).unwrap()[0]),
+            "C0FFEE"
+        );
+        assert_eq!(decode(&display, &entry), Some(0xc0ffee));
+
+        // A minus and an underscore are both a single bar, so swapping the two
+        // wires swaps the symbols.
+        let bars = parse_display(&["- a".to_string(), "_ b".to_string()]).unwrap();
+        assert_eq!(bars.ambiguities(), [(0, 1)]);
+        let entry = scramble(&bars, "ab", &[0, 1]);
+        assert_eq!(bars.readings(&entry).unwrap(), [vec![0, 1], vec![1, 0]]);
+        assert_eq!(decode(&bars, &entry), None);
+
+        assert!(parse_display(&["0".to_string()]).is_err());
+        assert!(parse_display(&["0 ab1".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_diagnose() {
+        let digits = parse_digits("abcefg cf acdeg acdfg bcdf abdfg abdefg acf abcdefg abcdfg");
+        let display = Display::new(&digits).unwrap();
+        let wires = "deafgbc";
+        let wiring: Vec<usize> = (0..7)
+            .map(|w| wires.find((b'a' + w) as char).unwrap())
+            .collect();
+        let entry = scramble(&display, wires, &[5, 3, 5, 3]);
+        let reads = |diagnosis: &Diagnosis, entry: &Entry| diagnosis.report(&display, entry);
+
+        // Missing patterns still pin the wiring down when enough remain.
+        let (patterns, output) = entry.clone();
+        let partial = (patterns[..7].to_vec(), output.clone());
+        assert_eq!(display.readings(&partial), Some(vec![]));
+        let diagnoses = display.diagnose(&partial).unwrap();
+        assert_eq!(diagnoses.len(), 1);
+        assert_eq!(diagnoses[0].fault, None);
+        assert!(diagnoses[0].wirings.contains(&wiring));
+        assert!(reads(&diagnoses[0], &partial).ends_with("reads 5353"));
+
+        // Dead and stuck wires show up as a fault on the segment they drive.
+        let without = |p: &String| p.chars().filter(|&c| c != 'f').collect::<String>();
+        let dead = (
+            patterns.iter().map(without).collect(),
+            output.iter().map(without).collect(),
+        );
+        let diagnoses = display.diagnose(&dead).unwrap();
+        let diagnosis = diagnoses
+            .iter()
+            .find(|d| d.fault == Some(Fault::Dead(3)))
+            .unwrap();
+        assert_eq!(diagnosis.mismatched, 0);
+        assert!(diagnosis.wirings.contains(&wiring));
+        assert_eq!(
+            diagnosis.explain(
+                &display,
+                display.digits[8] & !(1 << 3),
+                &(0..7).collect::<Vec<usize>>()
+            ),
+            [0, 8]
+        );
+        assert_eq!(Fault::Dead(3).to_string(), "segment d dead");
+
+        let with = |p: &String| {
+            let mut p = p.clone();
+            if !p.contains('f') {
+                p.push('f');
+            }
+            p
+        };
+        let stuck = (
+            patterns.iter().map(with).collect(),
+            output.iter().map(with).collect(),
+        );
+        let diagnoses = display.diagnose(&stuck).unwrap();
+        assert!(diagnoses
+            .iter()
+            .any(|d| d.fault == Some(Fault::Stuck(3)) && d.wirings.contains(&wiring)));
+
+        // A garbled pattern is left unexplained by the closest wiring.
+        let mut garbled = entry.clone();
+        garbled.0[0] = "a".to_string();
+        let diagnoses = display.diagnose(&garbled).unwrap();
+        assert_eq!(diagnoses[0].fault, None);
+        assert_eq!(diagnoses[0].mismatched, 1);
+        assert_eq!(diagnoses[0].wirings, [wiring]);
+        assert_eq!(
+            reads(&diagnoses[0], &garbled),
+            "no faulty segment, 1 unexplained patterns, 1 wirings, reads 5353"
+        );
+
+        assert!(display.diagnose(&parse_entry("ah | ab").unwrap()).is_err());
+
+        let sixteen: Vec<String> = (0..16).map(|s| ((b'a' + s) as char).to_string()).collect();
+        let sixteen = Display::new(&sixteen).unwrap();
+        let err = sixteen
+            .diagnose(&parse_entry("a | a").unwrap())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "cannot diagnose a 16-segment display, at most 8 segments are supported"
+        );
+        assert_eq!(
+            solve_two_entry(&digits, &parse_entry("ah | ab").unwrap()),
+            None
+        );
     }
 }
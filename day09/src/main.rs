@@ -1,67 +1,446 @@
 use anyhow::{anyhow, Result};
-use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
+use std::str::FromStr;
 
 fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
     let input = read_input("input.txt")?;
     dbg!(solve1(&input));
-    dbg!(solve2(&input));
+    if args.iter().any(|a| a == "--features") {
+        for region in terrain_features(&input, Connectivity::Four) {
+            println!(
+                "{:?} at height {}: {:?}",
+                region.feature, region.height, region.cells
+            );
+        }
+        return Ok(());
+    }
+    if args.iter().any(|a| a == "--flood-fill") {
+        dbg!(solve2_flood_fill(&input));
+        return Ok(());
+    }
+
+    let mut config = BasinConfig::default();
+    if let Some(i) = args.iter().position(|a| a == "--walls") {
+        config.walls = args.get(i + 1).ok_or(anyhow!("missing walls"))?.parse()?;
+    }
+    if let Some(i) = args.iter().position(|a| a == "--connectivity") {
+        config.connectivity = args
+            .get(i + 1)
+            .ok_or(anyhow!("missing connectivity"))?
+            .parse()?;
+    }
+    config.drainage = args.iter().any(|a| a == "--drainage");
+    if args.iter().any(|a| a == "--labels") {
+        let basins = analyze_basins(&input, &config);
+        for i in 0..input.len() {
+            let row: String = basins
+                .row(i)
+                .iter()
+                .map(|l| l.map_or('#', |l| char::from_digit(l % 36, 36).unwrap()))
+                .collect();
+            println!("{}", row);
+        }
+    }
+    dbg!(solve2(&input, &config));
     Ok(())
 }
 
-fn solve1(heights: &Vec<Vec<u32>>) -> u32 {
-    low_points(&heights).map(|(_, _, n)| *n + 1).sum()
+fn solve1(heights: &[Vec<u32>]) -> u32 {
+    low_points(heights).map(|(_, _, n)| *n + 1).sum()
 }
 
-fn solve2(heights: &Vec<Vec<u32>>) -> usize {
-    let mut lps = low_points(&heights)
-        .map(|p| basin(&heights, p).count())
+fn solve2(heights: &[Vec<u32>], config: &BasinConfig) -> usize {
+    let mut sizes = analyze_basins(heights, config)
+        .basins
+        .iter()
+        .map(|b| b.size)
+        .collect::<Vec<usize>>();
+    sizes.sort();
+    sizes.reverse();
+    sizes.iter().take(3).product()
+}
+
+fn solve2_flood_fill(heights: &[Vec<u32>]) -> usize {
+    let mut lps = low_points(heights)
+        .map(|(i, j, _)| basin(heights, (i, j)).len())
         .collect::<Vec<usize>>();
     lps.sort();
     lps.reverse();
     lps.iter().take(3).product()
 }
 
-fn basin<'a>(
-    heights: &'a Vec<Vec<u32>>,
-    point: (usize, usize, &'a u32),
-) -> impl Iterator<Item = &'a u32> {
-    let mut result = HashSet::new();
-    grow_basin(&mut result, heights, point);
-    result.into_iter().map(|(_, _, h)| h)
+fn basin(heights: &[Vec<u32>], point: (usize, usize)) -> Vec<(usize, usize)> {
+    let mut visited: Vec<Vec<bool>> = heights.iter().map(|r| vec![false; r.len()]).collect();
+    let mut queue = VecDeque::from([point]);
+    let mut result = vec![];
+    visited[point.0][point.1] = true;
+    while let Some((i, j)) = queue.pop_front() {
+        result.push((i, j));
+        for (ni, nj, &h) in neighbours(heights, i, j) {
+            if h != 9 && !visited[ni][nj] {
+                visited[ni][nj] = true;
+                queue.push_back((ni, nj));
+            }
+        }
+    }
+    result
+}
+
+struct UnionFind {
+    parent: Vec<u32>,
+    rank: Vec<u8>,
+}
+
+impl UnionFind {
+    fn new(n: usize) -> UnionFind {
+        UnionFind {
+            parent: (0..n as u32).collect(),
+            rank: vec![0; n],
+        }
+    }
+
+    fn find(&mut self, mut x: u32) -> u32 {
+        while self.parent[x as usize] != x {
+            let grandparent = self.parent[self.parent[x as usize] as usize];
+            self.parent[x as usize] = grandparent;
+            x = grandparent;
+        }
+        x
+    }
+
+    fn union(&mut self, a: u32, b: u32) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        let (high, low) = if self.rank[a as usize] >= self.rank[b as usize] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[low as usize] = high;
+        if self.rank[high as usize] == self.rank[low as usize] {
+            self.rank[high as usize] += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Basin {
+    size: usize,
+    low_point: (usize, usize),
+}
+
+/// Row-major basin ids, numbered in the order basins are first met.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Basins {
+    width: usize,
+    labels: Vec<u32>,
+    basins: Vec<Basin>,
+}
+
+impl Basins {
+    const WALL: u32 = u32::MAX;
+
+    fn label(&self, i: usize, j: usize) -> Option<u32> {
+        Some(self.labels[i * self.width + j]).filter(|&l| l != Basins::WALL)
+    }
+
+    fn row(&self, i: usize) -> Vec<Option<u32>> {
+        (0..self.width).map(|j| self.label(i, j)).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Walls {
+    AtLeast(u32),
+    Heights(Vec<u32>),
+}
+
+impl Walls {
+    fn contains(&self, height: u32) -> bool {
+        match self {
+            Walls::AtLeast(h) => height >= *h,
+            Walls::Heights(hs) => hs.contains(&height),
+        }
+    }
+}
+
+impl FromStr for Walls {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.strip_prefix(">=") {
+            Some(h) => Ok(Walls::AtLeast(h.parse()?)),
+            None => Ok(Walls::Heights(
+                s.split(',')
+                    .map(|h| h.parse())
+                    .collect::<Result<Vec<u32>, _>>()?,
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Connectivity {
+    Four,
+    Eight,
 }
 
-fn grow_basin<'a>(
-    basin: &mut HashSet<(usize, usize, &'a u32)>,
-    heights: &'a Vec<Vec<u32>>,
-    point: (usize, usize, &'a u32),
-) {
-    basin.insert(point);
-    for p @ (_, _, &h) in neighbours(heights, point.0, point.1) {
-        if h != 9 && basin.insert(p) {
-            grow_basin(basin, heights, p)
+impl Connectivity {
+    fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Four => &[(-1, 0), (1, 0), (0, -1), (0, 1)],
+            Connectivity::Eight => &[
+                (-1, 0),
+                (1, 0),
+                (0, -1),
+                (0, 1),
+                (-1, -1),
+                (-1, 1),
+                (1, -1),
+                (1, 1),
+            ],
         }
     }
 }
 
-fn low_points(heights: &Vec<Vec<u32>>) -> impl Iterator<Item = (usize, usize, &u32)> + '_ {
+impl FromStr for Connectivity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "4" => Ok(Connectivity::Four),
+            "8" => Ok(Connectivity::Eight),
+            _ => Err(anyhow!("unknown connectivity: {}", s)),
+        }
+    }
+}
+
+/// With `drainage`, each cell joins the basin its steepest descent reaches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BasinConfig {
+    walls: Walls,
+    connectivity: Connectivity,
+    drainage: bool,
+}
+
+impl Default for BasinConfig {
+    fn default() -> Self {
+        BasinConfig {
+            walls: Walls::AtLeast(9),
+            connectivity: Connectivity::Four,
+            drainage: false,
+        }
+    }
+}
+
+fn analyze_basins(heights: &[Vec<u32>], config: &BasinConfig) -> Basins {
+    let width = heights.iter().map(|r| r.len()).max().unwrap_or(0);
+    let index = |i: usize, j: usize| (i * width + j) as u32;
+    let open = |i: usize, j: usize| {
+        adjacent(heights, i, j, config.connectivity).filter(|(_, _, &n)| !config.walls.contains(n))
+    };
+    let mut sets = UnionFind::new(heights.len() * width);
+    for (i, j, &h) in enumerate_grid(heights) {
+        if config.walls.contains(h) {
+            continue;
+        }
+        // Draining water crosses a flat stretch, so equal heights move as one.
+        for (ni, nj, &n) in open(i, j) {
+            if !config.drainage || n == h {
+                sets.union(index(i, j), index(ni, nj));
+            }
+        }
+    }
+
+    if config.drainage {
+        // Each flat region drains through its lowest exit, if it has one.
+        const NONE: u32 = u32::MAX;
+        let height = |cell: u32| heights[cell as usize / width][cell as usize % width];
+        let mut exits: Vec<u32> = vec![NONE; heights.len() * width];
+        for (i, j, &h) in enumerate_grid(heights) {
+            if config.walls.contains(h) {
+                continue;
+            }
+            let root = sets.find(index(i, j)) as usize;
+            for (ni, nj, &n) in open(i, j) {
+                if n < h && (exits[root] == NONE || n < height(exits[root])) {
+                    exits[root] = index(ni, nj);
+                }
+            }
+        }
+        for (root, exit) in exits.into_iter().enumerate() {
+            if exit != NONE {
+                sets.union(root as u32, exit);
+            }
+        }
+    }
+
+    // A root's own slot holds its basin's id once any of its cells is met.
+    const UNSEEN: u32 = Basins::WALL - 1;
+    let mut labels = vec![Basins::WALL; heights.len() * width];
+    for (i, j, &h) in enumerate_grid(heights) {
+        if !config.walls.contains(h) {
+            labels[index(i, j) as usize] = UNSEEN;
+        }
+    }
+    let mut basins: Vec<Basin> = vec![];
+    for (i, j, &h) in enumerate_grid(heights) {
+        if config.walls.contains(h) {
+            continue;
+        }
+        let root = sets.find(index(i, j)) as usize;
+        if labels[root] == UNSEEN {
+            labels[root] = basins.len() as u32;
+            basins.push(Basin {
+                size: 0,
+                low_point: (i, j),
+            });
+        }
+        let id = labels[root];
+        labels[index(i, j) as usize] = id;
+        let basin = &mut basins[id as usize];
+        basin.size += 1;
+        if h < heights[basin.low_point.0][basin.low_point.1] {
+            basin.low_point = (i, j);
+        }
+    }
+    Basins {
+        width,
+        labels,
+        basins,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Feature {
+    Minimum,
+    Maximum,
+    Saddle,
+}
+
+/// A connected plateau of equal height and the feature it forms.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Region {
+    feature: Feature,
+    height: u32,
+    cells: Vec<(usize, usize)>,
+}
+
+/// Strict low and high plateaus, and single-cell saddles.
+fn terrain_features(heights: &[Vec<u32>], connectivity: Connectivity) -> Vec<Region> {
+    let width = heights.iter().map(|r| r.len()).max().unwrap_or(0);
+    let index = |i: usize, j: usize| (i * width + j) as u32;
+    let mut sets = UnionFind::new(heights.len() * width);
+    for (i, j, &h) in enumerate_grid(heights) {
+        for (ni, nj, _) in adjacent(heights, i, j, connectivity).filter(|(_, _, &n)| n == h) {
+            sets.union(index(i, j), index(ni, nj));
+        }
+    }
+
+    let mut ids: Vec<Option<usize>> = vec![None; heights.len() * width];
+    let mut plateaus: Vec<Vec<(usize, usize)>> = vec![];
+    for (i, j, _) in enumerate_grid(heights) {
+        let root = sets.find(index(i, j)) as usize;
+        let id = *ids[root].get_or_insert_with(|| {
+            plateaus.push(vec![]);
+            plateaus.len() - 1
+        });
+        plateaus[id].push((i, j));
+    }
+
+    plateaus
+        .into_iter()
+        .filter_map(|cells| {
+            let (i, j) = cells[0];
+            let height = heights[i][j];
+            let boundary: Vec<u32> = cells
+                .iter()
+                .flat_map(|&(i, j)| adjacent(heights, i, j, connectivity))
+                .map(|(_, _, &n)| n)
+                .filter(|&n| n != height)
+                .collect();
+            let feature = if boundary.is_empty() {
+                None
+            } else if boundary.iter().all(|&n| n > height) {
+                Some(Feature::Minimum)
+            } else if boundary.iter().all(|&n| n < height) {
+                Some(Feature::Maximum)
+            } else if cells.len() == 1 && is_saddle(heights, i, j) {
+                Some(Feature::Saddle)
+            } else {
+                None
+            };
+            feature.map(|feature| Region {
+                feature,
+                height,
+                cells,
+            })
+        })
+        .collect()
+}
+
+fn is_saddle(heights: &[Vec<u32>], i: usize, j: usize) -> bool {
+    const RING: [(isize, isize); 8] = [
+        (-1, -1),
+        (-1, 0),
+        (-1, 1),
+        (0, 1),
+        (1, 1),
+        (1, 0),
+        (1, -1),
+        (0, -1),
+    ];
+    let h = heights[i][j];
+    let higher: Vec<bool> = RING
+        .iter()
+        .filter_map(|&(di, dj)| {
+            let n = *heights
+                .get(i.checked_add_signed(di)?)?
+                .get(j.checked_add_signed(dj)?)?;
+            (n != h).then_some(n > h)
+        })
+        .collect();
+    let changes = (0..higher.len())
+        .filter(|&k| higher[k] != higher[(k + 1) % higher.len()])
+        .count();
+    changes >= 4
+}
+
+fn low_points(heights: &[Vec<u32>]) -> impl Iterator<Item = (usize, usize, &u32)> + '_ {
     enumerate_grid(heights).filter(|(i, j, _)| is_low_point(heights, *i, *j))
 }
 
-fn enumerate_grid<T>(grid: &Vec<Vec<T>>) -> impl Iterator<Item = (usize, usize, &T)> + '_ {
+fn enumerate_grid<T>(grid: &[Vec<T>]) -> impl Iterator<Item = (usize, usize, &T)> + '_ {
     (0..grid.len())
         .flat_map(|i| (0..grid[i].len()).map(move |j| (i, j)))
         .map(|(i, j)| (i, j, &grid[i][j]))
 }
 
-fn is_low_point(heights: &Vec<Vec<u32>>, i: usize, j: usize) -> bool {
+fn is_low_point(heights: &[Vec<u32>], i: usize, j: usize) -> bool {
     neighbours(heights, i, j).all(|(_, _, n)| n > &heights[i][j])
 }
 
+fn adjacent(
+    heights: &[Vec<u32>],
+    i: usize,
+    j: usize,
+    connectivity: Connectivity,
+) -> impl Iterator<Item = (usize, usize, &u32)> + '_ {
+    connectivity.offsets().iter().filter_map(move |&(di, dj)| {
+        let ni = i.checked_add_signed(di)?;
+        let nj = j.checked_add_signed(dj)?;
+        Some((ni, nj, heights.get(ni)?.get(nj)?))
+    })
+}
+
 fn neighbours(
-    heights: &Vec<Vec<u32>>,
+    heights: &[Vec<u32>],
     i: usize,
     j: usize,
 ) -> impl Iterator<Item = (usize, usize, &u32)> + '_ {
@@ -100,18 +479,228 @@ fn digits(s: String) -> Result<Vec<u32>> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{solve1, solve2};
+    use crate::{
+        analyze_basins, basin, low_points, solve1, solve2, solve2_flood_fill, terrain_features,
+        Basin, BasinConfig, Connectivity, Feature, Region, Walls,
+    };
+
+    const HEIGHTS: [[u32; 10]; 5] = [
+        [2, 1, 9, 9, 9, 4, 3, 2, 1, 0],
+        [3, 9, 8, 7, 8, 9, 4, 9, 2, 1],
+        [9, 8, 5, 6, 7, 8, 9, 8, 9, 2],
+        [8, 7, 6, 7, 8, 9, 6, 7, 8, 9],
+        [9, 8, 9, 9, 9, 6, 5, 6, 7, 8],
+    ];
+
+    fn example() -> Vec<Vec<u32>> {
+        HEIGHTS.iter().map(|r| r.to_vec()).collect()
+    }
 
     #[test]
     fn test_solution() {
-        let heights = vec![
-            vec![2, 1, 9, 9, 9, 4, 3, 2, 1, 0],
-            vec![3, 9, 8, 7, 8, 9, 4, 9, 2, 1],
-            vec![9, 8, 5, 6, 7, 8, 9, 8, 9, 2],
-            vec![8, 7, 6, 7, 8, 9, 6, 7, 8, 9],
-            vec![9, 8, 9, 9, 9, 6, 5, 6, 7, 8],
-        ];
+        let heights = example();
         assert_eq!(solve1(&heights), 15);
-        assert_eq!(solve2(&heights), 1134);
+        assert_eq!(solve2(&heights, &BasinConfig::default()), 1134);
+        assert_eq!(solve2_flood_fill(&heights), 1134);
+
+        let basins = analyze_basins(&heights, &BasinConfig::default());
+        assert_eq!(
+            basins.basins,
+            [
+                Basin {
+                    size: 3,
+                    low_point: (0, 1)
+                },
+                Basin {
+                    size: 9,
+                    low_point: (0, 9)
+                },
+                Basin {
+                    size: 14,
+                    low_point: (2, 2)
+                },
+                Basin {
+                    size: 9,
+                    low_point: (4, 6)
+                },
+            ]
+        );
+        assert_eq!(
+            basins.row(1),
+            [
+                Some(0),
+                None,
+                Some(2),
+                Some(2),
+                Some(2),
+                None,
+                Some(1),
+                None,
+                Some(1),
+                Some(1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_label_basins() {
+        // An irregular pattern with walls on roughly a third of the cells.
+        let heights: Vec<Vec<u32>> = (0..40)
+            .map(|i| {
+                (0..60)
+                    .map(|j| ((i * i * 7 + j * j * 3 + i * j * 5 + i) % 13).min(9) as u32)
+                    .collect()
+            })
+            .collect();
+        let basins = analyze_basins(&heights, &BasinConfig::default());
+        assert!(basins.basins.len() > 10);
+        for (id, b) in basins.basins.iter().enumerate() {
+            let cells = basin(&heights, b.low_point);
+            assert_eq!(cells.len(), b.size);
+            assert!(cells
+                .iter()
+                .all(|&(i, j)| basins.label(i, j) == Some(id as u32)));
+            assert!(cells
+                .iter()
+                .all(|&(i, j)| heights[i][j] >= heights[b.low_point.0][b.low_point.1]));
+        }
+        let labelled = (0..40).flat_map(|i| basins.row(i)).flatten().count();
+        assert_eq!(labelled, basins.basins.iter().map(|b| b.size).sum());
+
+        // A serpentine basin deep enough to overflow a recursive fill.
+        let heights: Vec<Vec<u32>> = (0..600)
+            .map(|i| {
+                (0..600)
+                    .map(|j| match (i % 4, j) {
+                        (1, j) if j != 599 => 9,
+                        (3, 0) => 0,
+                        (3, _) => 9,
+                        _ => 1,
+                    })
+                    .collect()
+            })
+            .collect();
+        let basins = analyze_basins(&heights, &BasinConfig::default());
+        assert_eq!(basins.basins.len(), 1);
+        assert_eq!(basin(&heights, (0, 0)).len(), basins.basins[0].size);
+    }
+
+    #[test]
+    fn test_basin_config() {
+        let heights = example();
+        let sizes = |config: &BasinConfig| {
+            analyze_basins(&heights, config)
+                .basins
+                .iter()
+                .map(|b| b.size)
+                .collect::<Vec<usize>>()
+        };
+        let default = BasinConfig::default();
+        assert_eq!(sizes(&default), [3, 9, 14, 9]);
+        assert_eq!(
+            sizes(&BasinConfig {
+                walls: "8,9".parse().unwrap(),
+                ..default.clone()
+            }),
+            sizes(&BasinConfig {
+                walls: ">=8".parse().unwrap(),
+                ..default.clone()
+            })
+        );
+        assert_eq!(">=8".parse::<Walls>().unwrap(), Walls::AtLeast(8));
+        assert!("8,x".parse::<Walls>().is_err());
+        assert!("6".parse::<Connectivity>().is_err());
+
+        // Only a diagonal joins the two cells.
+        let diagonal = vec![vec![0, 9], vec![9, 0]];
+        let eight = BasinConfig {
+            connectivity: "8".parse().unwrap(),
+            ..default.clone()
+        };
+        assert_eq!(analyze_basins(&diagonal, &default).basins.len(), 2);
+        assert_eq!(analyze_basins(&diagonal, &eight).basins.len(), 1);
+        assert_eq!(analyze_basins(&heights, &eight).basins.len(), 1);
+
+        let drainage = BasinConfig {
+            drainage: true,
+            ..default.clone()
+        };
+        let ridge = vec![vec![0, 1, 2, 3, 1, 0]];
+        let basins = analyze_basins(&ridge, &drainage);
+        assert_eq!(
+            basins.row(0),
+            [Some(0), Some(0), Some(0), Some(1), Some(1), Some(1)]
+        );
+        assert_eq!(basins.basins[1].low_point, (0, 5));
+        let shelf = vec![vec![3, 2, 2, 2, 1]];
+        let basins = analyze_basins(&shelf, &drainage);
+        assert_eq!(basins.basins.len(), 1);
+        assert_eq!(basins.basins[0].low_point, (0, 4));
+        let shelf = vec![vec![0, 5, 5, 5, 5, 5, 5, 3]];
+        let basins = analyze_basins(&shelf, &drainage);
+        assert_eq!(
+            basins.row(0),
+            [
+                Some(0),
+                Some(0),
+                Some(0),
+                Some(0),
+                Some(0),
+                Some(0),
+                Some(0),
+                Some(1)
+            ]
+        );
+        let pool = vec![vec![3, 1, 1, 3, 2]];
+        let basins = analyze_basins(&pool, &drainage);
+        assert_eq!(basins.row(0), [Some(0), Some(0), Some(0), Some(0), Some(1)]);
+
+        // Every walled basin of the example drains to its single low point.
+        let basins = analyze_basins(&heights, &drainage);
+        let walled = analyze_basins(&heights, &BasinConfig::default());
+        assert_eq!(basins, walled);
+    }
+
+    #[test]
+    fn test_terrain_features() {
+        let heights = example();
+        let minima: Vec<(usize, usize)> = terrain_features(&heights, Connectivity::Four)
+            .into_iter()
+            .filter(|r| r.feature == Feature::Minimum)
+            .flat_map(|r| r.cells)
+            .collect();
+        let low: Vec<(usize, usize)> = low_points(&heights).map(|(i, j, _)| (i, j)).collect();
+        assert_eq!(minima, low);
+
+        // The pit is two cells wide, so no single cell is strictly lowest.
+        let pit = vec![vec![5, 5, 5, 5], vec![5, 2, 2, 5], vec![5, 5, 5, 5]];
+        assert_eq!(low_points(&pit).count(), 0);
+        let features = terrain_features(&pit, Connectivity::Four);
+        assert_eq!(
+            features[1],
+            Region {
+                feature: Feature::Minimum,
+                height: 2,
+                cells: vec![(1, 1), (1, 2)]
+            }
+        );
+        assert_eq!(features[0].feature, Feature::Maximum);
+        assert_eq!(features.len(), 2);
+
+        let pass = vec![vec![5, 9, 5], vec![1, 5, 1], vec![5, 9, 5]];
+        let features = terrain_features(&pass, Connectivity::Four);
+        let find = |feature: Feature| {
+            features
+                .iter()
+                .filter(|r| r.feature == feature)
+                .flat_map(|r| r.cells.clone())
+                .collect::<Vec<(usize, usize)>>()
+        };
+        assert_eq!(find(Feature::Saddle), [(1, 1)]);
+        assert_eq!(find(Feature::Maximum), [(0, 1), (2, 1)]);
+        assert_eq!(find(Feature::Minimum), [(1, 0), (1, 2)]);
+
+        let flat = vec![vec![3, 3], vec![3, 3]];
+        assert!(terrain_features(&flat, Connectivity::Eight).is_empty());
     }
 }
@@ -1,103 +1,396 @@
-use anyhow;
+use anyhow::anyhow;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
 
 fn main() -> anyhow::Result<()> {
     let input = read_input("input.txt")?;
-    dbg!(syntax_error_score(&input));
-    dbg!(autocomplete_score(&input));
+    let args: Vec<String> = std::env::args().collect();
+    let brackets = match args.iter().position(|a| a == "--brackets") {
+        Some(i) => {
+            let path = args.get(i + 1).ok_or(anyhow!("missing brackets file"))?;
+            BracketSet::parse(&read_input(path)?)?
+        }
+        None => BracketSet::default(),
+    };
+    if args.iter().any(|a| a == "--diagnostics") {
+        for (n, line) in input.iter().enumerate() {
+            if let Err(e) = validate_line(line, &brackets) {
+                println!("{}\n", e.diagnostic(&brackets, "input.txt", n + 1, line));
+            }
+        }
+    }
+    if args.iter().any(|a| a == "--repair") {
+        for line in &input {
+            match validate_line(line, &brackets) {
+                Ok(()) => println!("{}", line),
+                Err(e) => println!("{}", e.repair(&brackets).apply(line)),
+            }
+        }
+    }
+    dbg!(syntax_error_score(&input, &brackets));
+    dbg!(autocomplete_score(&input, &brackets));
     Ok(())
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Pair {
+    open: String,
+    close: String,
+    error_score: u64,
+    completion_score: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BracketSet {
+    pairs: Vec<Pair>,
+    ignore: Vec<char>,
+    completion_base: u64,
+}
+
+impl Default for BracketSet {
+    fn default() -> Self {
+        let pair = |open: &str, close: &str, error_score, completion_score| Pair {
+            open: open.to_string(),
+            close: close.to_string(),
+            error_score,
+            completion_score,
+        };
+        BracketSet {
+            pairs: vec![
+                pair("(", ")", 3, 1),
+                pair("[", "]", 57, 2),
+                pair("{", "}", 1197, 3),
+                pair("<", ">", 25137, 4),
+            ],
+            ignore: vec![],
+            completion_base: 5,
+        }
+    }
+}
+
+impl BracketSet {
+    /// Lines of `open close error-score completion-score`, `ignore <chars>` or `base <n>`.
+    fn parse(lines: &[String]) -> anyhow::Result<BracketSet> {
+        let mut brackets = BracketSet {
+            pairs: vec![],
+            ignore: vec![],
+            completion_base: 5,
+        };
+        for line in lines.iter().map(|l| l.trim()) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(chars) = line.strip_prefix("ignore ") {
+                let chars = chars.trim().replace("\\s", " ").replace("\\t", "\t");
+                brackets.ignore.extend(chars.chars());
+                continue;
+            }
+            if let Some(base) = line.strip_prefix("base ") {
+                brackets.completion_base = base.trim().parse()?;
+                continue;
+            }
+            match line.split_whitespace().collect::<Vec<&str>>().as_slice() {
+                [open, close, error_score, completion_score] => brackets.pairs.push(Pair {
+                    open: open.to_string(),
+                    close: close.to_string(),
+                    error_score: error_score.parse()?,
+                    completion_score: completion_score.parse()?,
+                }),
+                _ => return Err(anyhow!("invalid bracket pair: {}", line)),
+            }
+        }
+        if brackets.pairs.is_empty() {
+            return Err(anyhow!("no bracket pairs"));
+        }
+        Ok(brackets)
+    }
+
+    fn closing(&self, close: &str) -> Option<&Pair> {
+        self.pairs.iter().find(|p| p.close == close)
+    }
+
+    /// The longest delimiter `s` starts with, preferring openers on a tie.
+    fn token(&self, s: &str) -> Option<(usize, bool)> {
+        self.pairs
+            .iter()
+            .enumerate()
+            .flat_map(|(i, p)| [(i, true, &p.open), (i, false, &p.close)])
+            .filter(|(_, _, d)| s.starts_with(d.as_str()))
+            .max_by_key(|&(_, open, d)| (d.len(), open))
+            .map(|(i, open, _)| (i, open))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum ValidationErr {
-    Corrupted(char),
-    Incomplete(Vec<char>),
+    Corrupted {
+        position: usize,
+        found: String,
+        expected: Option<String>,
+        opened_at: Option<usize>,
+    },
+    Unexpected {
+        position: usize,
+        found: char,
+    },
+    /// `missing` closes the open brackets innermost first.
+    Incomplete {
+        position: usize,
+        missing: Vec<String>,
+        opened_at: Vec<usize>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Repair {
+    Insert {
+        position: usize,
+        text: String,
+    },
+    Replace {
+        position: usize,
+        len: usize,
+        text: String,
+    },
+    Delete {
+        position: usize,
+        len: usize,
+    },
+}
+
+impl Repair {
+    fn apply(&self, line: &str) -> String {
+        match self {
+            Repair::Insert { position, text } => {
+                format!("{}{}{}", &line[..*position], text, &line[*position..])
+            }
+            Repair::Replace {
+                position,
+                len,
+                text,
+            } => format!("{}{}{}", &line[..*position], text, &line[position + len..]),
+            Repair::Delete { position, len } => {
+                format!("{}{}", &line[..*position], &line[position + len..])
+            }
+        }
+    }
 }
 
 impl ValidationErr {
-    fn score(&self) -> u64 {
+    fn score(&self, brackets: &BracketSet) -> u64 {
         match self {
-            Self::Corrupted(')') => 3,
-            Self::Corrupted(']') => 57,
-            Self::Corrupted('}') => 1197,
-            Self::Corrupted('>') => 25137,
-            Self::Corrupted(_) => 0,
-            Self::Incomplete(cs) => cs.iter().fold(0, |s, c| {
-                s * 5
-                    + match c {
-                        ')' => 1,
-                        ']' => 2,
-                        '}' => 3,
-                        '>' => 4,
-                        _ => 0,
-                    }
+            Self::Corrupted { found, .. } => brackets.closing(found).map_or(0, |p| p.error_score),
+            Self::Unexpected { .. } => 0,
+            Self::Incomplete { missing, .. } => missing.iter().fold(0, |s, c| {
+                s * brackets.completion_base + brackets.closing(c).map_or(0, |p| p.completion_score)
             }),
         }
     }
+
+    /// The smallest edit that gets past this error.
+    fn repair(&self, brackets: &BracketSet) -> Repair {
+        match self {
+            Self::Corrupted {
+                position,
+                found,
+                expected: Some(expected),
+                ..
+            } => Repair::Replace {
+                position: *position,
+                len: found.len(),
+                text: expected.clone(),
+            },
+            Self::Corrupted {
+                position, found, ..
+            } => Repair::Insert {
+                position: *position,
+                text: brackets
+                    .closing(found)
+                    .map_or(String::new(), |p| p.open.clone()),
+            },
+            Self::Unexpected { position, found } => Repair::Delete {
+                position: *position,
+                len: found.len_utf8(),
+            },
+            Self::Incomplete {
+                position, missing, ..
+            } => Repair::Insert {
+                position: *position,
+                text: missing.concat(),
+            },
+        }
+    }
+
+    fn diagnostic(&self, brackets: &BracketSet, path: &str, line_no: usize, line: &str) -> String {
+        let column = |p: usize| line[..p].chars().count();
+        let (message, position, width, opened_at, label) = match self {
+            Self::Corrupted {
+                position,
+                found,
+                expected: Some(expected),
+                opened_at,
+            } => (
+                format!("expected `{}`, found `{}`", expected, found),
+                *position,
+                found.chars().count(),
+                opened_at.iter().copied().collect(),
+                format!("expected `{}`", expected),
+            ),
+            Self::Corrupted {
+                position, found, ..
+            } => (
+                format!("unexpected closing delimiter `{}`", found),
+                *position,
+                found.chars().count(),
+                vec![],
+                "nothing to close".to_string(),
+            ),
+            Self::Unexpected { position, found } => (
+                format!("unexpected character `{}`", found),
+                *position,
+                1,
+                vec![],
+                "not a delimiter".to_string(),
+            ),
+            Self::Incomplete {
+                position,
+                missing,
+                opened_at,
+            } => (
+                "unclosed delimiters".to_string(),
+                *position,
+                1,
+                opened_at.clone(),
+                format!("expected `{}`", missing.concat()),
+            ),
+        };
+
+        let mut underline = vec![' '; column(position) + width];
+        for &o in &opened_at {
+            underline[column(o)] = '-';
+        }
+        for c in &mut underline[column(position)..] {
+            *c = '^';
+        }
+        let underline: String = underline.into_iter().collect();
+        let help = match self.repair(brackets) {
+            Repair::Insert { position, text } => {
+                format!("insert `{}` at {}:{}", text, line_no, column(position) + 1)
+            }
+            Repair::Replace {
+                position,
+                len,
+                text,
+            } => format!(
+                "replace `{}` at {}:{} with `{}`",
+                &line[position..position + len],
+                line_no,
+                column(position) + 1,
+                text
+            ),
+            Repair::Delete { position, len } => format!(
+                "remove `{}` at {}:{}",
+                &line[position..position + len],
+                line_no,
+                column(position) + 1
+            ),
+        };
+
+        let gutter = " ".repeat(line_no.to_string().len());
+        format!(
+            "error: {}\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {} {}\n{} = help: {}",
+            message,
+            gutter,
+            path,
+            line_no,
+            column(position) + 1,
+            gutter,
+            line_no,
+            line,
+            gutter,
+            underline,
+            label,
+            gutter,
+            help
+        )
+    }
 }
 
-fn syntax_error_score<'a, I: IntoIterator<Item = &'a String>>(input: I) -> u64 {
+fn syntax_error_score<'a, I: IntoIterator<Item = &'a String>>(
+    input: I,
+    brackets: &BracketSet,
+) -> u64 {
     input
         .into_iter()
-        .map(|l| validate_line(l))
+        .map(|l| validate_line(l, brackets))
         .filter_map(|r| r.err())
         .map(|e| match e {
-            ValidationErr::Corrupted(_) => e.score(),
+            ValidationErr::Corrupted { .. } => e.score(brackets),
             _ => 0,
         })
         .sum()
 }
 
-fn autocomplete_score<'a, I: IntoIterator<Item = &'a String>>(input: I) -> u64 {
+fn autocomplete_score<'a, I: IntoIterator<Item = &'a String>>(
+    input: I,
+    brackets: &BracketSet,
+) -> Option<u64> {
     let mut scores = input
         .into_iter()
-        .map(|l| validate_line(l))
+        .map(|l| validate_line(l, brackets))
         .filter_map(|r| match r {
-            Err(e @ ValidationErr::Incomplete(_)) => Some(e.score()),
+            Err(e @ ValidationErr::Incomplete { .. }) => Some(e.score(brackets)),
             _ => None,
         })
         .collect::<Vec<u64>>();
     scores.sort();
-    scores[scores.len() / 2]
+    scores.get(scores.len() / 2).copied()
 }
 
-fn validate_line(s: &str) -> Result<(), ValidationErr> {
-    let mut stack = vec![];
-
-    for c in s.chars() {
-        if c == '(' || c == '[' || c == '{' || c == '<' {
-            stack.push(c);
-        } else if let Some(&last) = stack.last() {
-            if (c == ')' && last == '(')
-                || (c == ']' && last == '[')
-                || (c == '}' && last == '{')
-                || (c == '>' && last == '<')
-            {
-                stack.pop();
-            } else {
-                return Err(ValidationErr::Corrupted(c));
-            }
-        } else {
-            return Err(ValidationErr::Corrupted(c));
+fn validate_line(s: &str, brackets: &BracketSet) -> Result<(), ValidationErr> {
+    let mut stack: Vec<(usize, &Pair)> = vec![];
+    let mut position = 0;
+
+    while let Some(c) = s[position..].chars().next() {
+        let rest = &s[position..];
+        if brackets.ignore.contains(&c) {
+            position += c.len_utf8();
+            continue;
+        }
+        // Closing the innermost pair comes first, so a delimiter that both
+        // opens and closes (like a quote) closes when it can.
+        if let Some(&(_, pair)) = stack.last().filter(|(_, p)| rest.starts_with(&p.close)) {
+            stack.pop();
+            position += pair.close.len();
+            continue;
+        }
+        match brackets.token(rest) {
+            Some((i, true)) => {
+                let pair = &brackets.pairs[i];
+                stack.push((position, pair));
+                position += pair.open.len();
+            }
+            Some((i, false)) => {
+                let last = stack.last();
+                return Err(ValidationErr::Corrupted {
+                    position,
+                    found: brackets.pairs[i].close.clone(),
+                    expected: last.map(|(_, p)| p.close.clone()),
+                    opened_at: last.map(|&(p, _)| p),
+                });
+            }
+            None => return Err(ValidationErr::Unexpected { position, found: c }),
         }
     }
 
     if !stack.is_empty() {
         stack.reverse();
-        return Err(ValidationErr::Incomplete(
-            stack
-                .iter()
-                .map(|c| match c {
-                    '(' => ')',
-                    '[' => ']',
-                    '{' => '}',
-                    '<' => '>',
-                    _ => *c,
-                })
-                .collect(),
-        ));
+        return Err(ValidationErr::Incomplete {
+            position: s.len(),
+            missing: stack.iter().map(|(_, p)| p.close.clone()).collect(),
+            opened_at: stack.iter().map(|&(p, _)| p).collect(),
+        });
     }
 
     Ok(())
@@ -110,7 +403,9 @@ fn read_input<P: AsRef<std::path::Path>>(path: P) -> anyhow::Result<Vec<String>>
 
 #[cfg(test)]
 mod tests {
-    use crate::{autocomplete_score, syntax_error_score};
+    use crate::{
+        autocomplete_score, syntax_error_score, validate_line, BracketSet, Repair, ValidationErr,
+    };
 
     #[test]
     fn test_scores() {
@@ -127,7 +422,157 @@ mod tests {
             "<{([{{}}[<[[[<>{}]]]>[]]".to_string(),
         ];
 
-        assert_eq!(syntax_error_score(&input), 26397);
-        assert_eq!(autocomplete_score(&input), 288957);
+        let brackets = BracketSet::default();
+        assert_eq!(syntax_error_score(&input, &brackets), 26397);
+        assert_eq!(autocomplete_score(&input, &brackets), Some(288957));
+        assert_eq!(
+            autocomplete_score(&["()".to_string(), "(]".to_string()], &brackets),
+            None
+        );
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let brackets = BracketSet::default();
+        let line = "{([(<{}[<>[]}>{[]{[(<()>";
+        let err = validate_line(line, &brackets).unwrap_err();
+        assert_eq!(
+            err,
+            ValidationErr::Corrupted {
+                position: 12,
+                found: "}".to_string(),
+                expected: Some("]".to_string()),
+                opened_at: Some(7),
+            }
+        );
+        assert_eq!(
+            err.repair(&brackets),
+            Repair::Replace {
+                position: 12,
+                len: 1,
+                text: "]".to_string()
+            }
+        );
+        assert_eq!(
+            err.diagnostic(&brackets, "input.txt", 3, line),
+            "error: expected `]`, found `}`
+ --> input.txt:3:13
+  |
+3 | {([(<{}[<>[]}>{[]{[(<()>
+  |        -    ^ expected `]`
+  = help: replace `}` at 3:13 with `]`"
+        );
+
+        let line = "[({(<(())[]>[[{[]{<()<>>";
+        let err = validate_line(line, &brackets).unwrap_err();
+        assert_eq!(err.score(&brackets), 288957);
+        assert_eq!(
+            err.diagnostic(&brackets, "input.txt", 1, line),
+            "error: unclosed delimiters
+ --> input.txt:1:25
+  |
+1 | [({(<(())[]>[[{[]{<()<>>
+  | ----        ---  -      ^ expected `}}]])})]`
+  = help: insert `}}]])})]` at 1:25"
+        );
+        assert_eq!(
+            validate_line(&err.repair(&brackets).apply(line), &brackets),
+            Ok(())
+        );
+
+        let line = "()]";
+        let err = validate_line(line, &brackets).unwrap_err();
+        assert_eq!(err.repair(&brackets).apply(line), "()[]");
+        assert!(err
+            .diagnostic(&brackets, "input.txt", 12, line)
+            .starts_with("error: unexpected closing delimiter `]`\n  --> input.txt:12:3\n"));
+
+        let line = "x()";
+        let err = validate_line(line, &brackets).unwrap_err();
+        assert_eq!(
+            err.repair(&brackets),
+            Repair::Delete {
+                position: 0,
+                len: 1
+            }
+        );
+        assert_eq!(err.repair(&brackets).apply(line), "()");
+        assert_eq!(
+            err.diagnostic(&brackets, "input.txt", 1, line),
+            "error: unexpected character `x`
+ --> input.txt:1:1
+  |
+1 | x()
+  | ^ not a delimiter
+  = help: remove `x` at 1:1"
+        );
+    }
+
+    #[test]
+    fn test_bracket_set() {
+        let config: Vec<String> = [
+            "# open close error completion",
+            "( ) 3 1",
+            "/* */ 10 2",
+            "ignore \\sabcxyz",
+            "base 3",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+        let brackets = BracketSet::parse(&config).unwrap();
+        assert_eq!(brackets.ignore, [' ', 'a', 'b', 'c', 'x', 'y', 'z']);
+        assert_eq!(brackets.completion_base, 3);
+
+        assert_eq!(validate_line("/* a (b) */ (c)", &brackets), Ok(()));
+        let line = "/* (a */ x";
+        let err = validate_line(line, &brackets).unwrap_err();
+        assert_eq!(
+            err,
+            ValidationErr::Corrupted {
+                position: 6,
+                found: "*/".to_string(),
+                expected: Some(")".to_string()),
+                opened_at: Some(3),
+            }
+        );
+        assert_eq!(err.score(&brackets), 10);
+        assert_eq!(err.repair(&brackets).apply(line), "/* (a ) x");
+        assert!(err
+            .diagnostic(&brackets, "input.txt", 1, line)
+            .contains("1 | /* (a */ x\n  |    -  ^^ expected `)`\n"));
+
+        let line = "/* ( /*";
+        let err = validate_line(line, &brackets).unwrap_err();
+        assert_eq!(
+            err,
+            ValidationErr::Incomplete {
+                position: 7,
+                missing: vec!["*/".to_string(), ")".to_string(), "*/".to_string()],
+                opened_at: vec![5, 3, 0],
+            }
+        );
+        assert_eq!(err.score(&brackets), (2 * 3 + 1) * 3 + 2);
+        assert_eq!(err.repair(&brackets).apply(line), "/* ( /**/)*/");
+        assert_eq!(
+            validate_line(&err.repair(&brackets).apply(line), &brackets),
+            Ok(())
+        );
+
+        // Without `[` in the set it's an unknown character worth nothing.
+        let err = validate_line("([)", &brackets).unwrap_err();
+        assert_eq!(
+            err,
+            ValidationErr::Unexpected {
+                position: 1,
+                found: '['
+            }
+        );
+        assert_eq!(err.score(&brackets), 0);
+
+        let quotes = BracketSet::parse(&["| | 1 1".to_string()]).unwrap();
+        assert_eq!(validate_line("||", &quotes), Ok(()));
+        assert!(BracketSet::parse(&["( ) 3".to_string()]).is_err());
+        assert!(BracketSet::parse(&[]).is_err());
     }
 }